@@ -0,0 +1,253 @@
+//! Low-precision solar and lunar ephemeris (Jean Meeus, "Astronomical Algorithms").
+//!
+//! Good to roughly 0.01° for the Sun and a few hundredths of a degree for the Moon over
+//! 1900–2100, i.e. lunar phases land within a few minutes of the published times.
+//! ΔT (TT − UT, about a minute over that span) is ignored.
+
+use chrono::{DateTime, Utc};
+
+/// Julian Day of the J2000.0 epoch.
+const J2000: f64 = 2451545.0;
+/// Mean synodic month in days.
+pub const SYNODIC_MONTH: f64 = 29.530588861;
+/// Mean tropical year in days.
+pub const TROPICAL_YEAR: f64 = 365.24219;
+
+/// Julian Day for a UTC instant.
+pub fn julian_day(t: DateTime<Utc>) -> f64 {
+    // Unix epoch is JD 2440587.5
+    t.timestamp_millis() as f64 / 86400000.0 + 2440587.5
+}
+
+/// UTC instant for a Julian Day.
+pub fn from_julian_day(jd: f64) -> DateTime<Utc> {
+    let millis = ((jd - 2440587.5) * 86_400_000.0).round() as i64;
+    DateTime::from_timestamp_millis(millis).unwrap_or_default()
+}
+
+/// Normalizes an angle to [0, 360).
+pub fn normalize_degrees(deg: f64) -> f64 {
+    deg.rem_euclid(360.0)
+}
+
+/// Signed difference `a - b` in (-180, 180].
+pub fn angle_difference(a: f64, b: f64) -> f64 {
+    180.0 - (b - a + 180.0).rem_euclid(360.0)
+}
+
+fn centuries(jd: f64) -> f64 {
+    (jd - J2000) / 36525.0
+}
+
+fn sin_deg(deg: f64) -> f64 {
+    deg.to_radians().sin()
+}
+
+/// Longitude of the Moon's ascending node, used for the nutation correction.
+fn node_longitude(t: f64) -> f64 {
+    125.04452 - 1934.136261 * t
+}
+
+/// Apparent geocentric ecliptic longitude of the Sun in degrees (Meeus ch. 25).
+pub fn sun_longitude(jd: f64) -> f64 {
+    let t = centuries(jd);
+    let l0 = 280.46646 + 36000.76983 * t + 0.0003032 * t * t;
+    let m = 357.52911 + 35999.05029 * t - 0.0001537 * t * t;
+    let c = (1.914602 - 0.004817 * t - 0.000014 * t * t) * sin_deg(m)
+        + (0.019993 - 0.000101 * t) * sin_deg(2.0 * m)
+        + 0.000289 * sin_deg(3.0 * m);
+    // Aberration and nutation in longitude
+    normalize_degrees(l0 + c - 0.00569 - 0.00478 * sin_deg(node_longitude(t)))
+}
+
+/// Periodic terms for the Moon's longitude (Meeus table 47.A):
+/// multiples of D, M, M', F and the coefficient in 1e-6 degrees.
+const MOON_LONGITUDE_TERMS: &[(i8, i8, i8, i8, i32)] = &[
+    (0, 0, 1, 0, 6_288_774),
+    (2, 0, -1, 0, 1_274_027),
+    (2, 0, 0, 0, 658_314),
+    (0, 0, 2, 0, 213_618),
+    (0, 1, 0, 0, -185_116),
+    (0, 0, 0, 2, -114_332),
+    (2, 0, -2, 0, 58_793),
+    (2, -1, -1, 0, 57_066),
+    (2, 0, 1, 0, 53_322),
+    (2, -1, 0, 0, 45_758),
+    (0, 1, -1, 0, -40_923),
+    (1, 0, 0, 0, -34_720),
+    (0, 1, 1, 0, -30_383),
+    (2, 0, 0, -2, 15_327),
+    (0, 0, 1, 2, -12_528),
+    (0, 0, 1, -2, 10_980),
+    (4, 0, -1, 0, 10_675),
+    (0, 0, 3, 0, 10_034),
+    (4, 0, -2, 0, 8_548),
+    (2, 1, -1, 0, -7_888),
+    (2, 1, 0, 0, -6_766),
+    (1, 0, -1, 0, -5_163),
+    (1, 1, 0, 0, 4_987),
+    (2, -1, 1, 0, 4_036),
+    (2, 0, 2, 0, 3_994),
+    (4, 0, 0, 0, 3_861),
+    (2, 0, -3, 0, 3_665),
+    (0, 1, -2, 0, -2_689),
+    (2, 0, -1, 2, -2_602),
+    (2, -1, -2, 0, 2_390),
+    (1, 0, 1, 0, -2_348),
+    (2, -2, 0, 0, 2_236),
+    (0, 1, 2, 0, -2_120),
+    (0, 2, 0, 0, -2_069),
+    (2, -2, -1, 0, 2_048),
+    (2, 0, 1, -2, -1_773),
+    (2, 0, 0, 2, -1_595),
+    (4, -1, -1, 0, 1_215),
+    (0, 0, 2, 2, -1_110),
+    (3, 0, -1, 0, -892),
+    (2, 1, 1, 0, -810),
+    (4, -1, -2, 0, 759),
+    (0, 2, -1, 0, -713),
+    (2, 2, -1, 0, -700),
+    (2, 1, -2, 0, 691),
+    (2, -1, 0, -2, 596),
+    (4, 0, 1, 0, 549),
+    (0, 0, 4, 0, 537),
+    (4, -1, 0, 0, 520),
+    (1, 0, -2, 0, -487),
+    (2, 1, 0, -2, -399),
+    (0, 0, 2, -2, -381),
+    (1, 1, 1, 0, 351),
+    (3, 0, -2, 0, -340),
+    (4, 0, -3, 0, 330),
+    (2, -1, 2, 0, 327),
+    (0, 2, 1, 0, -323),
+    (1, 1, -1, 0, 299),
+    (2, 0, 3, 0, 294),
+];
+
+/// Apparent geocentric ecliptic longitude of the Moon in degrees (Meeus ch. 47).
+pub fn moon_longitude(jd: f64) -> f64 {
+    let t = centuries(jd);
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let t4 = t3 * t;
+
+    let lp = 218.3164477 + 481267.88123421 * t - 0.0015786 * t2 + t3 / 538841.0 - t4 / 65194000.0;
+    let d = 297.8501921 + 445267.1114034 * t - 0.0018819 * t2 + t3 / 545868.0 - t4 / 113065000.0;
+    let m = 357.5291092 + 35999.0502909 * t - 0.0001536 * t2 + t3 / 24490000.0;
+    let mp = 134.9633964 + 477198.8675055 * t + 0.0087414 * t2 + t3 / 69699.0 - t4 / 14712000.0;
+    let f = 93.2720950 + 483202.0175233 * t - 0.0036539 * t2 - t3 / 3526000.0 + t4 / 863310000.0;
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t2;
+
+    let mut sum = 0.0;
+    for &(cd, cm, cmp, cf, coeff) in MOON_LONGITUDE_TERMS.iter() {
+        let arg = cd as f64 * d + cm as f64 * m + cmp as f64 * mp + cf as f64 * f;
+        // Terms involving the Sun's anomaly shrink with the Earth's orbital eccentricity
+        let scale = match cm.abs() {
+            1 => e,
+            2 => e * e,
+            _ => 1.0,
+        };
+        sum += coeff as f64 * scale * sin_deg(arg);
+    }
+
+    let a1 = 119.75 + 131.849 * t;
+    sum += 3958.0 * sin_deg(a1) + 1962.0 * sin_deg(lp - f);
+
+    normalize_degrees(lp + sum / 1000000.0 - 0.00478 * sin_deg(node_longitude(t)))
+}
+
+/// Moon − Sun ecliptic longitude in [0, 360): 0 = new, 90 = first quarter,
+/// 180 = full, 270 = last quarter.
+pub fn moon_elongation(jd: f64) -> f64 {
+    normalize_degrees(moon_longitude(jd) - sun_longitude(jd))
+}
+
+/// Solves `angle(jd) == target` for the first root after `after`, given the
+/// angle's mean rate in degrees per day (Newton iteration on the mean rate).
+fn next_crossing(angle: fn(f64) -> f64, rate: f64, target: f64, after: f64) -> f64 {
+    let mut jd = after + normalize_degrees(target - angle(after)) / rate;
+    for _ in 0..20 {
+        let step = angle_difference(target, angle(jd)) / rate;
+        jd += step;
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+    jd
+}
+
+/// First instant after `after` (JD) at which the Sun's apparent longitude is `target` degrees.
+/// Multiples of 15° are the 24 solar terms (二十四節気).
+pub fn next_sun_longitude(target: f64, after: f64) -> f64 {
+    next_crossing(sun_longitude, 360.0 / TROPICAL_YEAR, target, after)
+}
+
+/// First instant after `after` (JD) at which the Moon's elongation is `target` degrees
+/// (0 = new moon, 180 = full moon).
+pub fn next_moon_elongation(target: f64, after: f64) -> f64 {
+    next_crossing(moon_elongation, 360.0 / SYNODIC_MONTH, target, after)
+}
+
+/// Illuminated fraction of the lunar disc (0.0 – 1.0) for a given elongation.
+pub fn illuminated_fraction(elongation: f64) -> f64 {
+    (1.0 - elongation.to_radians().cos()) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn jd(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> f64 {
+        julian_day(Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap())
+    }
+
+    #[test]
+    fn julian_day_of_j2000() {
+        assert!((jd(2000, 1, 1, 12, 0) - J2000).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sun_longitude_at_equinox_and_solstice() {
+        // March equinox 2024-03-20 03:06 UTC, June solstice 2024-06-20 20:51 UTC
+        assert!(angle_difference(sun_longitude(jd(2024, 3, 20, 3, 6)), 0.0).abs() < 0.02);
+        assert!(angle_difference(sun_longitude(jd(2024, 6, 20, 20, 51)), 90.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn known_new_moons() {
+        // 0.2° of elongation is about 25 minutes
+        for when in [
+            jd(2000, 1, 6, 18, 14),
+            jd(2017, 8, 21, 18, 30),
+            jd(2024, 1, 11, 11, 57),
+            jd(2009, 7, 22, 2, 35),
+        ] {
+            let e = angle_difference(moon_elongation(when), 0.0);
+            assert!(e.abs() < 0.2, "elongation {} at JD {}", e, when);
+        }
+    }
+
+    #[test]
+    fn known_full_moons() {
+        for when in [
+            jd(2000, 1, 21, 4, 40),
+            jd(2023, 8, 31, 1, 35),
+            jd(2024, 1, 25, 17, 54),
+        ] {
+            let e = angle_difference(moon_elongation(when), 180.0);
+            assert!(e.abs() < 0.2, "elongation {} at JD {}", e, when);
+            assert!(illuminated_fraction(moon_elongation(when)) > 0.999);
+        }
+    }
+
+    #[test]
+    fn finds_next_events() {
+        // Winter solstice 2024-12-21 09:20 UTC, new moon 2024-12-30 22:27 UTC
+        let start = jd(2024, 12, 1, 0, 0);
+        assert!((next_sun_longitude(270.0, start) - jd(2024, 12, 21, 9, 20)).abs() < 0.01);
+        assert!((next_moon_elongation(0.0, start) - jd(2024, 12, 1, 6, 21)).abs() < 0.02);
+        let after = jd(2024, 12, 2, 0, 0);
+        assert!((next_moon_elongation(0.0, after) - jd(2024, 12, 30, 22, 27)).abs() < 0.02);
+    }
+}
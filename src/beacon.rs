@@ -0,0 +1,138 @@
+//! Mock draws from a public-style beacon: any hex string (e.g. a block hash pasted in
+//! by hand) decides the winning numbers, and anyone can recompute them offline.
+//!
+//! The mapping, version 1:
+//!
+//! 1. The beacon is hex (an optional `0x`, any case) decoded to bytes.
+//! 2. Block `k` (k = 0, 1, 2, …) is
+//!    `SHA-256("loto_random_cli draw v1" || game || 0x00 || beacon || k as u32 big-endian)`,
+//!    where `game` is `loto6`, `loto7` or `mini`. Each block is read as eight
+//!    big-endian `u32` words, block after block.
+//! 3. Balls stay in an ascending pool. For a pool of `m` balls, a word `w` is used only
+//!    if `w < 2^32 - 2^32 mod m` (so every ball is equally likely); the ball at index
+//!    `w mod m` is drawn and leaves the pool.
+//! 4. The main numbers are drawn first, then the bonus numbers from the balls left.
+//!    Main numbers are shown sorted, bonus numbers in the order drawn.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::game::GameType;
+
+const DOMAIN: &[u8] = b"loto_random_cli draw v1";
+
+/// The winning numbers of one draw, mock or entered by hand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Drawing {
+    pub main: Vec<u32>,
+    pub bonus: Vec<u32>,
+}
+
+/// Decodes a beacon given as hex.
+pub fn parse_beacon(s: &str) -> Result<Vec<u8>, String> {
+    let s = s.trim();
+    let digits = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    if digits.is_empty() {
+        return Err("シードが空です".to_string());
+    }
+    hex::decode(digits).map_err(|e| format!("16進数の文字列を指定してください: {}", e))
+}
+
+/// The words of step 2, endlessly.
+fn words(game: GameType, beacon: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    (0u32..).flat_map(move |k| {
+        let block = Sha256::new()
+            .chain_update(DOMAIN)
+            .chain_update(game.name().as_bytes())
+            .chain_update([0])
+            .chain_update(beacon)
+            .chain_update(k.to_be_bytes())
+            .finalize();
+        let words: Vec<u32> = block
+            .chunks(4)
+            .map(|w| u32::from_be_bytes([w[0], w[1], w[2], w[3]]))
+            .collect();
+        words
+    })
+}
+
+/// Draws the winning numbers of `game` from `beacon`.
+pub fn draw(game: GameType, beacon: &[u8]) -> Drawing {
+    let (range, picks) = game.config();
+    let mut pool: Vec<u32> = range.collect();
+    let mut words = words(game, beacon);
+    let mut drawn = Vec::with_capacity(picks + game.bonus_count());
+
+    while drawn.len() < picks + game.bonus_count() {
+        let m = pool.len() as u64;
+        // Largest multiple of m that fits in 32 bits; words at or above it are skipped
+        let limit = (1u64 << 32) / m * m;
+        let w = words.next().unwrap_or_default() as u64;
+        if w < limit {
+            drawn.push(pool.remove((w % m) as usize));
+        }
+    }
+
+    let bonus = drawn.split_off(picks);
+    drawn.sort();
+    Drawing { main: drawn, bonus }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beacons_parse_as_hex() {
+        assert_eq!(
+            parse_beacon("0xDEADbeef").unwrap(),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(parse_beacon(" 00ff ").unwrap(), vec![0, 255]);
+        assert!(parse_beacon("").is_err());
+        assert!(parse_beacon("0x").is_err());
+        assert!(parse_beacon("abc").is_err());
+        assert!(parse_beacon("xyz0").is_err());
+    }
+
+    #[test]
+    fn every_game_draws_distinct_numbers_in_range() {
+        for game in [GameType::Loto6, GameType::Loto7, GameType::Mini] {
+            let (range, picks) = game.config();
+            for seed in 0u32..200 {
+                let drawing = draw(game, &seed.to_be_bytes());
+                assert_eq!(drawing.main.len(), picks);
+                assert_eq!(drawing.bonus.len(), game.bonus_count());
+                assert!(drawing.main.windows(2).all(|w| w[0] < w[1]));
+                let all: Vec<u32> = drawing.main.iter().chain(&drawing.bonus).copied().collect();
+                assert!(all.iter().all(|n| range.contains(n)));
+                for (i, n) in all.iter().enumerate() {
+                    assert!(!all[i + 1..].contains(n));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_mapping_is_pinned() {
+        // Changing these numbers breaks every published mock draw: bump the version instead
+        let beacon =
+            parse_beacon("00000000000000000001a7c3b1e4b7b5e8e5b4e9f2a1c3d4e5f60718293a4b5c")
+                .unwrap();
+        let pinned = [
+            (GameType::Loto6, vec![3, 19, 21, 28, 31, 32], vec![13]),
+            (GameType::Loto7, vec![5, 7, 8, 10, 26, 32, 36], vec![33, 31]),
+            (GameType::Mini, vec![3, 11, 12, 22, 24], vec![27]),
+        ];
+        for (game, main, bonus) in pinned {
+            assert_eq!(draw(game, &beacon), Drawing { main, bonus }, "{:?}", game);
+        }
+        assert_ne!(
+            draw(GameType::Loto6, &beacon[1..]),
+            draw(GameType::Loto6, &beacon)
+        );
+    }
+}
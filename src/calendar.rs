@@ -0,0 +1,564 @@
+//! Japanese lunisolar calendar (旧暦) for 1900–2100.
+//!
+//! Months begin on the JST day of the new moon; a month's number comes from the
+//! principal solar term (中気) it contains, the month holding the winter solstice
+//! being the 11th. When thirteen months separate two winter solstices, the first
+//! month without a 中気 becomes the leap month. The table below was generated from
+//! the ephemeris in [`crate::astro`] with exactly these rules (see the tests).
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+
+/// First and last Gregorian years that can be converted.
+pub const FIRST_YEAR: i32 = 1900;
+pub const LAST_YEAR: i32 = 2100;
+
+/// First lunar year in [`LUNAR_YEARS`]; January 1900 still belongs to lunar 1899.
+const TABLE_START: i32 = FIRST_YEAR - 1;
+
+/// One entry per lunar year from 1899 to 2100:
+/// bits 0–12: month lengths in calendar order (1 = 30 days, 0 = 29 days),
+/// bits 13–16: leap month number (0 = none),
+/// bits 17–22: day of the Gregorian year (0-based) on which month 1 day 1 falls.
+#[rustfmt::skip]
+const LUNAR_YEARS: [u32; 202] = [
+    0x500ad5, 0x3d16d2, 0x620752, 0x4c0ea5, 0x38ad4a, 0x5c054b, 0x440a97, 0x309556,
+    0x56055a, 0x400b55, 0x2a56d2, 0x500752, 0x3ad725, 0x600b25, 0x480a4b, 0x32b29b,
+    0x580aad, 0x44056a, 0x2c4b69, 0x520ba9, 0x3efb52, 0x640d92, 0x4c0d25, 0x36ba4d,
+    0x5c0956, 0x4602b5, 0x2e95ad, 0x5606d4, 0x400da9, 0x2c5d92, 0x500e92, 0x3acd26,
+    0x5e0527, 0x480a57, 0x32b2b6, 0x580ada, 0x4406d4, 0x2e6ea9, 0x520749, 0x3cf693,
+    0x620a93, 0x4c052b, 0x34ca5b, 0x5a096d, 0x460b6a, 0x329b54, 0x560ba4, 0x400b49,
+    0x2a5a93, 0x500a95, 0x38f52b, 0x5e052d, 0x480aad, 0x34b56a, 0x580db2, 0x440da4,
+    0x2e7d49, 0x540d4a, 0x3d1a95, 0x620a96, 0x4c0556, 0x36cab5, 0x5a0ad5, 0x4606d2,
+    0x308ea5, 0x560ea5, 0x400e4a, 0x2a6c96, 0x4e0a9b, 0x3af556, 0x5e056a, 0x480b59,
+    0x34b752, 0x5a0752, 0x420725, 0x2c964b, 0x520a4b, 0x3d12ab, 0x6002ad, 0x4a056b,
+    0x36cb69, 0x5c0da9, 0x460d92, 0x309b25, 0x560d25, 0x415a4d, 0x640a56, 0x4e02b6,
+    0x38d5ad, 0x6006d4, 0x480da9, 0x34bd92, 0x5a0e92, 0x440d26, 0x2c6a56, 0x500a57,
+    0x3d12b6, 0x620b5a, 0x4c06d4, 0x36aec9, 0x5c0749, 0x460693, 0x2e9527, 0x54052b,
+    0x3e0a5b, 0x2a555a, 0x4e036a, 0x38fb55, 0x600ba4, 0x4a0b49, 0x32ba93, 0x580a95,
+    0x42052d, 0x2c6a5d, 0x500aad, 0x3d35aa, 0x6205d2, 0x4c0da5, 0x36bd4a, 0x5c0d4a,
+    0x460a95, 0x30952d, 0x540556, 0x3e0ab5, 0x2a55aa, 0x5006d2, 0x38cea5, 0x5e0ea5,
+    0x4a0e4a, 0x34ac96, 0x560c9b, 0x42055a, 0x2c6ad5, 0x520b69, 0x3d7752, 0x620752,
+    0x4c0b25, 0x36d64b, 0x5a0a4b, 0x4404ab, 0x2ea55b, 0x54056d, 0x3e0b69, 0x2a5b52,
+    0x500d92, 0x3afd25, 0x5e0d25, 0x480a4d, 0x32b4ad, 0x5802b6, 0x4005b5, 0x2c6da9,
+    0x520da9, 0x3f1d92, 0x620e92, 0x4c0d26, 0x36ca56, 0x5a0a57, 0x4404d6, 0x2e86b5,
+    0x5406d5, 0x400ec9, 0x2a6e92, 0x4e0693, 0x38f52b, 0x5e052b, 0x460a5b, 0x32b55a,
+    0x58056a, 0x420b55, 0x2c9749, 0x520b49, 0x3d1a93, 0x620a95, 0x4a052d, 0x34ca6d,
+    0x5a0ab5, 0x4605aa, 0x2e8ba5, 0x540da5, 0x400d4a, 0x2a7a95, 0x4e0c95, 0x38f52e,
+    0x5e0556, 0x480ab5, 0x32b5b2, 0x5806d2, 0x420ea5, 0x2e9e4a, 0x52064a, 0x3b0c97,
+    0x600cab, 0x4c055a, 0x34cad5, 0x5a0b69, 0x460752, 0x308ea5, 0x540b25, 0x3e064b,
+    0x287497, 0x4e04ab,
+];
+
+/// A date on the 旧暦.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LunarDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub leap: bool,
+}
+
+impl std::fmt::Display for LunarDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "旧暦 {}年 {}{}月 {}日",
+            self.year,
+            if self.leap { "閏" } else { "" },
+            self.month,
+            self.day
+        )
+    }
+}
+
+/// The 24 solar terms (二十四節気), indexed by solar longitude / 15°, starting at 春分 (0°).
+pub const SOLAR_TERMS: [&str; 24] = [
+    "春分", "清明", "穀雨", "立夏", "小満", "芒種", "夏至", "小暑", "大暑", "立秋", "処暑", "白露",
+    "秋分", "寒露", "霜降", "立冬", "小雪", "大雪", "冬至", "小寒", "大寒", "立春", "雨水", "啓蟄",
+];
+
+/// The five phases (五行).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FiveElement {
+    Wood,
+    Fire,
+    Earth,
+    Metal,
+    Water,
+}
+
+impl FiveElement {
+    pub fn kanji(&self) -> &'static str {
+        match self {
+            FiveElement::Wood => "木",
+            FiveElement::Fire => "火",
+            FiveElement::Earth => "土",
+            FiveElement::Metal => "金",
+            FiveElement::Water => "水",
+        }
+    }
+
+    /// The element this one feeds in the 相生 cycle.
+    pub fn generates(&self) -> FiveElement {
+        match self {
+            FiveElement::Wood => FiveElement::Fire,
+            FiveElement::Fire => FiveElement::Earth,
+            FiveElement::Earth => FiveElement::Metal,
+            FiveElement::Metal => FiveElement::Water,
+            FiveElement::Water => FiveElement::Wood,
+        }
+    }
+
+    /// Last digits tied to the element by the 河図 (Yellow River map).
+    pub fn hetu_digits(&self) -> [usize; 2] {
+        match self {
+            FiveElement::Water => [1, 6],
+            FiveElement::Fire => [2, 7],
+            FiveElement::Wood => [3, 8],
+            FiveElement::Metal => [4, 9],
+            FiveElement::Earth => [5, 0],
+        }
+    }
+}
+
+pub const STEMS: [&str; 10] = ["甲", "乙", "丙", "丁", "戊", "己", "庚", "辛", "壬", "癸"];
+pub const BRANCHES: [&str; 12] = [
+    "子", "丑", "寅", "卯", "辰", "巳", "午", "未", "申", "酉", "戌", "亥",
+];
+
+/// A position in the sexagenary cycle (干支): 0 = 甲子 … 59 = 癸亥.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sexagenary(u8);
+
+impl Sexagenary {
+    pub fn from_index(index: i64) -> Self {
+        Sexagenary(index.rem_euclid(60) as u8)
+    }
+
+    /// The 干支 of a (lunar) year; 1984 was 甲子.
+    pub fn for_year(year: i32) -> Self {
+        Self::from_index(year as i64 - 4)
+    }
+
+    pub fn index(&self) -> u8 {
+        self.0
+    }
+
+    /// Heavenly stem, 0 = 甲 … 9 = 癸.
+    pub fn stem(&self) -> u8 {
+        self.0 % 10
+    }
+
+    /// Earthly branch, 0 = 子 … 11 = 亥.
+    pub fn branch(&self) -> u8 {
+        self.0 % 12
+    }
+
+    /// 甲乙 wood, 丙丁 fire, 戊己 earth, 庚辛 metal, 壬癸 water.
+    pub fn stem_element(&self) -> FiveElement {
+        match self.stem() / 2 {
+            0 => FiveElement::Wood,
+            1 => FiveElement::Fire,
+            2 => FiveElement::Earth,
+            3 => FiveElement::Metal,
+            _ => FiveElement::Water,
+        }
+    }
+}
+
+impl std::fmt::Display for Sexagenary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            STEMS[self.stem() as usize],
+            BRANCHES[self.branch() as usize]
+        )
+    }
+}
+
+/// The lunar year a date belongs to, so that the animal changes at 旧正月.
+/// Outside the table's range the Gregorian year is used.
+pub fn lunar_year(date: NaiveDate) -> i32 {
+    to_lunar(date).map_or(date.year(), |l| l.year)
+}
+
+/// The solar-term year and month of a date: the year turns at 立春 and the month
+/// index runs 0 = 寅月 (from 立春) … 11 = 丑月 (from 小寒), each starting on the JST
+/// day on which its 節 falls.
+pub fn solar_month(date: NaiveDate) -> (i32, i64) {
+    // Sun longitude at the end of the JST day, so a 節 on this day already counts
+    let jd = crate::astro::julian_day(jst_midnight(date + Duration::days(1)));
+    let longitude = crate::astro::sun_longitude(jd);
+    let month = ((longitude - 315.0).rem_euclid(360.0) / 30.0) as i64;
+
+    // January and early February before 立春 still belong to the previous year
+    let year = if date.month() <= 2 && month >= 10 {
+        date.year() - 1
+    } else {
+        date.year()
+    };
+    (year, month)
+}
+
+/// The 干支 of a day; 2000-01-01 was 戊午 (54).
+pub fn day_kanshi(date: NaiveDate) -> Sexagenary {
+    Sexagenary::from_index(date.num_days_from_ce() as i64 + 14)
+}
+
+/// The year, month and day pillars (年柱・月柱・日柱) of a birth date.
+///
+/// Year and month follow [`solar_month`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FourPillars {
+    pub year: Sexagenary,
+    pub month: Sexagenary,
+    pub day: Sexagenary,
+}
+
+impl FourPillars {
+    pub fn from_date(date: NaiveDate) -> Self {
+        let (year, month) = solar_month(date);
+        let year = Sexagenary::for_year(year);
+
+        // 五虎遁: the 寅月 stem follows from the year stem (甲・己 years open with 丙寅)
+        let first_stem = (year.stem() as i64 % 5) * 2 + 2;
+        let month_stem = (first_stem + month) % 10;
+        let month_branch = (month + 2) % 12;
+        // Stem and branch pin down the position in the cycle (both have the same parity)
+        let month = (0..60)
+            .find(|i| i % 10 == month_stem && i % 12 == month_branch)
+            .map_or(Sexagenary::from_index(0), Sexagenary::from_index);
+
+        let day = day_kanshi(date);
+
+        FourPillars { year, month, day }
+    }
+
+    /// The day stem, i.e. the person themself in 算命学.
+    pub fn day_master(&self) -> FiveElement {
+        self.day.stem_element()
+    }
+
+    /// 天中殺: the two branches missing from the day pillar's ten-day week (旬).
+    pub fn void_branches(&self) -> [u8; 2] {
+        let start = self.day.index() - self.day.stem();
+        [(start + 10) % 12, (start + 11) % 12]
+    }
+}
+
+/// Name and JST date of the first solar term after `jd`.
+pub fn next_solar_term(jd: f64) -> (&'static str, NaiveDate) {
+    let index = (crate::astro::sun_longitude(jd) / 15.0).floor() as usize % 24 + 1;
+    let when = crate::astro::next_sun_longitude((index * 15) as f64 % 360.0, jd);
+    (SOLAR_TERMS[index % 24], jst_date(when))
+}
+
+/// JST calendar date of a Julian Day.
+pub fn jst_date(jd: f64) -> NaiveDate {
+    (crate::astro::from_julian_day(jd) + Duration::hours(9)).date_naive()
+}
+
+/// The UTC instant at which a JST calendar day begins.
+pub fn jst_midnight(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default()) - Duration::hours(9)
+}
+
+fn entry(lunar_year: i32) -> Option<u32> {
+    let idx = lunar_year.checked_sub(TABLE_START)?;
+    LUNAR_YEARS.get(usize::try_from(idx).ok()?).copied()
+}
+
+fn new_year(lunar_year: i32, entry: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(lunar_year, 1, 1).unwrap_or_default()
+        + Duration::days(((entry >> 17) & 0x3f) as i64)
+}
+
+/// Months of a lunar year in calendar order: (month, leap, days).
+fn months(entry: u32) -> impl Iterator<Item = (u32, bool, u32)> {
+    let leap = (entry >> 13) & 0xf;
+    let count = if leap > 0 { 13 } else { 12 };
+    (0..count).map(move |i| {
+        let days = if entry & (1 << i) != 0 { 30 } else { 29 };
+        match leap {
+            0 => (i + 1, false, days),
+            l if i < l => (i + 1, false, days),
+            l if i == l => (l, true, days),
+            _ => (i, false, days),
+        }
+    })
+}
+
+/// Converts a Gregorian (JST) date to the 旧暦, or `None` outside 1900–2100.
+pub fn to_lunar(date: NaiveDate) -> Option<LunarDate> {
+    if !(FIRST_YEAR..=LAST_YEAR).contains(&date.year()) {
+        return None;
+    }
+
+    let mut year = date.year();
+    let mut e = entry(year)?;
+    if date < new_year(year, e) {
+        year -= 1;
+        e = entry(year)?;
+    }
+
+    let mut offset = (date - new_year(year, e)).num_days() as u32;
+    for (month, leap, days) in months(e) {
+        if offset < days {
+            return Some(LunarDate {
+                year,
+                month,
+                day: offset + 1,
+                leap,
+            });
+        }
+        offset -= days;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astro;
+
+    fn jd_of(date: NaiveDate) -> f64 {
+        astro::julian_day(jst_midnight(date))
+    }
+
+    /// JST dates of every new moon between `from` and `to`.
+    fn new_moons(from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let mut result = Vec::new();
+        let mut jd = jd_of(from);
+        loop {
+            jd = astro::next_moon_elongation(0.0, jd);
+            let date = jst_date(jd);
+            if date > to {
+                return result;
+            }
+            result.push(date);
+            jd += 1.0;
+        }
+    }
+
+    /// JST dates of the principal terms (中気, multiples of 30°) between `from` and `to`.
+    fn principal_terms(from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let mut result = Vec::new();
+        let mut jd = jd_of(from);
+        let mut target = (astro::sun_longitude(jd) / 30.0).ceil() * 30.0;
+        loop {
+            jd = astro::next_sun_longitude(target % 360.0, jd);
+            let date = jst_date(jd);
+            if date > to {
+                return result;
+            }
+            result.push(date);
+            jd += 1.0;
+            target += 30.0;
+        }
+    }
+
+    fn winter_solstice(year: i32) -> NaiveDate {
+        let start = jd_of(NaiveDate::from_ymd_opt(year, 12, 1).unwrap());
+        jst_date(astro::next_sun_longitude(270.0, start))
+    }
+
+    /// Months from the 11th month of `year - 1` up to (not including) the 11th month
+    /// of `year`: (first day, month number, leap).
+    fn solstice_cycle(year: i32) -> Vec<(NaiveDate, u32, bool)> {
+        let (ws1, ws2) = (winter_solstice(year - 1), winter_solstice(year));
+        let moons = new_moons(ws1 - Duration::days(40), ws2 + Duration::days(40));
+        let terms = principal_terms(ws1 - Duration::days(40), ws2 + Duration::days(70));
+
+        let m11a = moons.iter().rposition(|d| *d <= ws1).unwrap();
+        let m11b = moons.iter().rposition(|d| *d <= ws2).unwrap();
+        let has_term = |i: usize| terms.iter().any(|t| moons[i] <= *t && *t < moons[i + 1]);
+        let leap = if m11b - m11a == 13 {
+            (m11a + 1..m11b).find(|&i| !has_term(i))
+        } else {
+            None
+        };
+
+        let mut month = 10;
+        (m11a..m11b)
+            .map(|i| {
+                let is_leap = Some(i) == leap;
+                if !is_leap {
+                    month = month % 12 + 1;
+                }
+                (moons[i], month, is_leap)
+            })
+            .collect()
+    }
+
+    fn compute_entry(year: i32) -> u32 {
+        let mut months: Vec<_> = solstice_cycle(year);
+        months.extend(solstice_cycle(year + 1));
+        months.extend(solstice_cycle(year + 2).into_iter().take(1));
+
+        let first = months.iter().position(|m| m.1 == 1 && !m.2).unwrap();
+        let next = first
+            + 1
+            + months[first + 1..]
+                .iter()
+                .position(|m| m.1 == 1 && !m.2)
+                .unwrap();
+
+        let mut entry = 0u32;
+        for i in first..next {
+            if (months[i + 1].0 - months[i].0).num_days() == 30 {
+                entry |= 1 << (i - first);
+            }
+            if months[i].2 {
+                entry |= months[i].1 << 13;
+            }
+        }
+        entry | (months[first].0.ordinal0() << 17)
+    }
+
+    /// Prints the table: `cargo test print_lunar_table -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn print_lunar_table() {
+        let entries: Vec<String> = (TABLE_START..=LAST_YEAR)
+            .map(|y| format!("0x{:06x}", compute_entry(y)))
+            .collect();
+        for line in entries.chunks(8) {
+            println!("    {},", line.join(", "));
+        }
+    }
+
+    #[test]
+    fn table_matches_ephemeris() {
+        for year in TABLE_START..=LAST_YEAR {
+            assert_eq!(
+                entry(year),
+                Some(compute_entry(year)),
+                "lunar year {}",
+                year
+            );
+        }
+    }
+
+    fn lunar(y: i32, m: u32, d: u32) -> LunarDate {
+        to_lunar(NaiveDate::from_ymd_opt(y, m, d).unwrap()).unwrap()
+    }
+
+    fn ld(year: i32, month: u32, day: u32, leap: bool) -> LunarDate {
+        LunarDate {
+            year,
+            month,
+            day,
+            leap,
+        }
+    }
+
+    #[test]
+    fn sexagenary_years() {
+        assert_eq!(Sexagenary::for_year(1984).to_string(), "甲子");
+        assert_eq!(Sexagenary::for_year(2024).to_string(), "甲辰");
+        assert_eq!(Sexagenary::for_year(2024).stem_element(), FiveElement::Wood);
+        assert_eq!(Sexagenary::for_year(2026).to_string(), "丙午");
+        assert_eq!(Sexagenary::for_year(1900).to_string(), "庚子");
+    }
+
+    #[test]
+    fn lunar_year_changes_at_lunar_new_year() {
+        assert_eq!(
+            lunar_year(NaiveDate::from_ymd_opt(1990, 1, 26).unwrap()),
+            1989
+        );
+        assert_eq!(
+            lunar_year(NaiveDate::from_ymd_opt(1990, 1, 27).unwrap()),
+            1990
+        );
+        assert_eq!(
+            lunar_year(NaiveDate::from_ymd_opt(2150, 1, 1).unwrap()),
+            2150
+        );
+    }
+
+    #[test]
+    fn four_pillars() {
+        let p = FourPillars::from_date(NaiveDate::from_ymd_opt(2000, 1, 1).unwrap());
+        // Before 立春: still 己卯, in the 丙子 month, on a 戊午 day
+        assert_eq!(p.year.to_string(), "己卯");
+        assert_eq!(p.month.to_string(), "丙子");
+        assert_eq!(p.day.to_string(), "戊午");
+        assert_eq!(p.day_master(), FiveElement::Earth);
+
+        let p = FourPillars::from_date(NaiveDate::from_ymd_opt(1984, 2, 5).unwrap());
+        assert_eq!(p.year.to_string(), "甲子");
+        assert_eq!(p.month.to_string(), "丙寅");
+        assert_eq!(p.day.to_string(), "己巳");
+
+        let p = FourPillars::from_date(NaiveDate::from_ymd_opt(2024, 6, 21).unwrap());
+        assert_eq!(p.year.to_string(), "甲辰");
+        assert_eq!(p.month.to_string(), "庚午");
+        assert_eq!(p.day.to_string(), "丙辰");
+
+        // 2024 opened on a 甲子 day
+        let p = FourPillars::from_date(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(p.day.index(), 0);
+    }
+
+    #[test]
+    fn pillars_turn_on_the_solar_term_day() {
+        // 立春 2024 fell on February 4th (JST)
+        let before = FourPillars::from_date(NaiveDate::from_ymd_opt(2024, 2, 3).unwrap());
+        let on = FourPillars::from_date(NaiveDate::from_ymd_opt(2024, 2, 4).unwrap());
+        assert_eq!(before.year.to_string(), "癸卯");
+        assert_eq!(before.month.to_string(), "乙丑");
+        assert_eq!(on.year.to_string(), "甲辰");
+        assert_eq!(on.month.to_string(), "丙寅");
+    }
+
+    #[test]
+    fn void_branches() {
+        // 甲子 week lacks 戌 and 亥; 甲寅 week lacks 子 and 丑
+        let mut p = FourPillars::from_date(NaiveDate::from_ymd_opt(2000, 1, 1).unwrap());
+        p.day = Sexagenary::from_index(0);
+        assert_eq!(p.void_branches(), [10, 11]);
+        p.day = Sexagenary::from_index(55);
+        assert_eq!(p.void_branches(), [0, 1]);
+    }
+
+    #[test]
+    fn known_new_years() {
+        assert_eq!(lunar(1900, 1, 31), ld(1900, 1, 1, false));
+        assert_eq!(lunar(2000, 2, 5), ld(2000, 1, 1, false));
+        assert_eq!(lunar(2024, 2, 9), ld(2023, 12, 30, false));
+        assert_eq!(lunar(2024, 2, 10), ld(2024, 1, 1, false));
+        assert_eq!(lunar(2025, 1, 29), ld(2025, 1, 1, false));
+    }
+
+    #[test]
+    fn known_leap_months() {
+        assert_eq!(lunar(2023, 3, 22), ld(2023, 2, 1, true));
+        assert_eq!(lunar(2020, 5, 23), ld(2020, 4, 1, true));
+        assert_eq!(lunar(2025, 7, 25), ld(2025, 6, 1, true));
+        // The "2033 problem" resolves to a leap 11th month
+        assert!(lunar(2033, 12, 22).leap);
+    }
+
+    #[test]
+    fn covers_the_whole_range() {
+        assert_eq!(lunar(1900, 1, 1), ld(1899, 12, 1, false));
+        assert!(to_lunar(NaiveDate::from_ymd_opt(2100, 12, 31).unwrap()).is_some());
+        assert!(to_lunar(NaiveDate::from_ymd_opt(1899, 12, 31).unwrap()).is_none());
+        assert!(to_lunar(NaiveDate::from_ymd_opt(2101, 1, 1).unwrap()).is_none());
+
+        let mut prev = lunar(1900, 1, 1);
+        let mut date = NaiveDate::from_ymd_opt(1900, 1, 2).unwrap();
+        while date.year() <= LAST_YEAR {
+            let cur = to_lunar(date).unwrap();
+            assert!(
+                cur.day == prev.day + 1 || (cur.day == 1 && prev.day >= 29),
+                "{}",
+                date
+            );
+            prev = cur;
+            date = date.succ_opt().unwrap();
+        }
+    }
+}
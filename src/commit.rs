@@ -0,0 +1,164 @@
+//! Commit–reveal: a SHA-256 commitment to everything that decides a run's tickets,
+//! published before the draw and checked against the revealed seed afterwards.
+
+use std::io;
+
+use sha2::{Digest, Sha256};
+
+use crate::game::GameType;
+use crate::generator::{self, Algorithm};
+
+/// Bumped whenever the committed text or the ticket generation changes.
+const VERSION: &str = "loto_random_cli commit v1";
+
+/// Everything that determines the tickets of a reproducible (non-Oracle) run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TicketSpec {
+    pub game: GameType,
+    pub algo: Algorithm,
+    pub count: usize,
+    pub seed: u64,
+}
+
+impl TicketSpec {
+    /// The committed text: a version line, then one `key=value` per line.
+    pub fn canonical(&self) -> String {
+        format!(
+            "{}\ngame={}\nalgo={}\nn={}\nseed={}\n",
+            VERSION,
+            self.game.name(),
+            self.algo.name(),
+            self.count,
+            self.seed
+        )
+    }
+
+    /// SHA-256 of the canonical text, as lowercase hex.
+    pub fn commitment(&self) -> String {
+        hex::encode(Sha256::digest(self.canonical()))
+    }
+
+    /// Whether `commitment` (hex, any case) was made for this spec.
+    pub fn matches(&self, commitment: &str) -> bool {
+        hex::decode(commitment.trim()).is_ok_and(|c| c == Sha256::digest(self.canonical())[..])
+    }
+
+    /// The command line that checks the commitment once the seed is revealed.
+    pub fn verify_command(&self) -> String {
+        format!(
+            "loto-random-cli verify {} --seed {} {} {} --n {}",
+            self.commitment(),
+            self.seed,
+            self.game.name(),
+            self.algo.name(),
+            self.count
+        )
+    }
+
+    /// The tickets the spec stands for, in order.
+    pub fn tickets(&self) -> io::Result<Vec<Vec<u32>>> {
+        let sampler = self.algo.sampler(self.game).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} cannot be replayed from a seed", self.algo.name()),
+            )
+        })?;
+        let mut tickets = Vec::with_capacity(self.count);
+        generator::generate(&sampler, self.count, self.seed, 1, |_, ticket| {
+            tickets.push(ticket.to_vec());
+            Ok(())
+        })?;
+        Ok(tickets)
+    }
+}
+
+/// The numbers of each row of a ticket CSV written by `--out` (`draw,n1,…,nK[,…]`).
+pub fn read_tickets(csv: &str, picks: usize) -> Result<Vec<Vec<u32>>, String> {
+    csv.lines()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(row, line)| {
+            let numbers: Vec<u32> = line
+                .split(',')
+                .skip(1)
+                .take(picks)
+                .map(|field| field.trim().parse())
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("{}行目が読めません: {}", row + 2, e))?;
+            if numbers.len() != picks {
+                return Err(format!("{}行目の数字が{}個ではありません", row + 2, picks));
+            }
+            Ok(numbers)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(seed: u64) -> TicketSpec {
+        TicketSpec {
+            game: GameType::Loto6,
+            algo: Algorithm::Pure,
+            count: 3,
+            seed,
+        }
+    }
+
+    #[test]
+    fn commitment_covers_every_parameter() {
+        let base = spec(42);
+        assert_eq!(
+            base.canonical(),
+            "loto_random_cli commit v1\ngame=loto6\nalgo=pure\nn=3\nseed=42\n"
+        );
+        let commitment = base.commitment();
+        assert_eq!(commitment.len(), 64);
+        assert!(base.matches(&commitment));
+        assert!(base.matches(&commitment.to_uppercase()));
+        assert!(!base.matches("not hex"));
+
+        let others = [
+            spec(43),
+            TicketSpec {
+                game: GameType::Mini,
+                ..spec(42)
+            },
+            TicketSpec {
+                algo: Algorithm::Spread,
+                ..spec(42)
+            },
+            TicketSpec {
+                count: 4,
+                ..spec(42)
+            },
+        ];
+        for other in others {
+            assert!(!other.matches(&commitment), "{:?}", other);
+        }
+    }
+
+    #[test]
+    fn revealed_tickets_match_the_csv() {
+        let base = spec(42);
+        let tickets = base.tickets().unwrap();
+        assert_eq!(tickets.len(), 3);
+        assert_eq!(tickets, base.tickets().unwrap());
+        let oracle = TicketSpec {
+            algo: Algorithm::Oracle,
+            ..spec(42)
+        };
+        assert!(oracle.tickets().is_err());
+
+        let mut csv = String::from("draw,n1,n2,n3,n4,n5,n6,commitment\n");
+        for (i, t) in tickets.iter().enumerate() {
+            let numbers: Vec<String> = t.iter().map(|n| n.to_string()).collect();
+            csv.push_str(&format!("{},{},abc\n", i + 1, numbers.join(",")));
+        }
+        assert_eq!(read_tickets(&csv, 6).unwrap(), tickets);
+        assert!(read_tickets("draw,n1\n1,x\n", 1).is_err());
+        assert!(read_tickets("draw,n1,n2\n1,5\n", 2).is_err());
+    }
+}
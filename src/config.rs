@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+
+/// Per-user configuration directory: `$XDG_CONFIG_HOME/loto_random_cli`,
+/// falling back to `~/.config/loto_random_cli`.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("loto_random_cli"))
+}
@@ -0,0 +1,284 @@
+use std::ops::RangeInclusive;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Weekday};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// ゲームタイプ
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameType {
+    Loto6,
+    Loto7,
+    Mini,
+}
+
+/// Draws take place at 18:45 JST.
+pub fn draw_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(18, 45, 0).unwrap_or_default()
+}
+
+/// A period during which a game was drawn on fixed weekdays.
+struct Era {
+    from: NaiveDate,
+    weekdays: &'static [Weekday],
+}
+
+const fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+    match NaiveDate::from_ymd_opt(y, m, d) {
+        Some(date) => date,
+        None => NaiveDate::MIN,
+    }
+}
+
+/// Known draw history; the first era starts with draw #1.
+const LOTO6_ERAS: &[Era] = &[
+    Era {
+        from: ymd(2000, 10, 5),
+        weekdays: &[Weekday::Thu],
+    },
+    Era {
+        from: ymd(2011, 4, 4),
+        weekdays: &[Weekday::Mon, Weekday::Thu],
+    },
+];
+const LOTO7_ERAS: &[Era] = &[Era {
+    from: ymd(2013, 4, 5),
+    weekdays: &[Weekday::Fri],
+}];
+const MINI_ERAS: &[Era] = &[Era {
+    from: ymd(1999, 4, 13),
+    weekdays: &[Weekday::Tue],
+}];
+
+impl GameType {
+    /// 各ゲームの数値範囲と口あたりの個数を返す
+    pub fn config(&self) -> (RangeInclusive<u32>, usize) {
+        match self {
+            GameType::Loto6 => (1..=43, 6),
+            GameType::Loto7 => (1..=37, 7),
+            GameType::Mini => (1..=31, 5),
+        }
+    }
+
+    /// ボーナス数字の個数
+    pub fn bonus_count(&self) -> usize {
+        match self {
+            GameType::Loto6 | GameType::Mini => 1,
+            GameType::Loto7 => 2,
+        }
+    }
+
+    /// Stable name used in commitments and the draw mapping; never derive it from the
+    /// variant name, or renaming a variant would change every published value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameType::Loto6 => "loto6",
+            GameType::Loto7 => "loto7",
+            GameType::Mini => "mini",
+        }
+    }
+
+    /// 1口の価格（円）
+    pub fn ticket_price(&self) -> u64 {
+        match self {
+            GameType::Loto6 | GameType::Mini => 200,
+            GameType::Loto7 => 300,
+        }
+    }
+
+    /// 当せん等級 (1等 = 1)。本数字とボーナス数字の一致数から決まり、はずれは None
+    pub fn prize_tier(&self, main_hits: usize, bonus_hits: usize) -> Option<u8> {
+        let bonus = bonus_hits > 0;
+        match (self, main_hits) {
+            (GameType::Loto6, 6) => Some(1),
+            (GameType::Loto6, 5) => Some(if bonus { 2 } else { 3 }),
+            (GameType::Loto6, 4) => Some(4),
+            (GameType::Loto6, 3) => Some(5),
+            (GameType::Loto7, 7) => Some(1),
+            (GameType::Loto7, 6) => Some(if bonus { 2 } else { 3 }),
+            (GameType::Loto7, 5) => Some(4),
+            (GameType::Loto7, 4) => Some(5),
+            (GameType::Loto7, 3) if bonus => Some(6),
+            (GameType::Mini, 5) => Some(1),
+            (GameType::Mini, 4) => Some(if bonus { 2 } else { 3 }),
+            (GameType::Mini, 3) => Some(4),
+            _ => None,
+        }
+    }
+
+    /// 1口あたりの当せん金の目安（円、1等から順に）。公表されている理論値で、
+    /// 実際の金額は回ごとに変わる（下位等級の一部を除く）
+    pub fn reference_prizes(&self) -> &'static [u64] {
+        match self {
+            GameType::Loto6 => &[200_000_000, 10_000_000, 300_000, 6_800, 1_000],
+            GameType::Loto7 => &[600_000_000, 7_300_000, 730_000, 9_100, 1_400, 1_000],
+            GameType::Mini => &[10_000_000, 150_000, 10_000, 1_000],
+        }
+    }
+
+    /// 表示名
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameType::Loto6 => "ロト6",
+            GameType::Loto7 => "ロト7",
+            GameType::Mini => "ミニロト",
+        }
+    }
+
+    fn eras(&self) -> &'static [Era] {
+        match self {
+            GameType::Loto6 => LOTO6_ERAS,
+            GameType::Loto7 => LOTO7_ERAS,
+            GameType::Mini => MINI_ERAS,
+        }
+    }
+
+    /// Whether a draw is scheduled on `date` (JST). There are no draws over the
+    /// New Year holidays (Dec 31 – Jan 3).
+    pub fn is_draw_day(&self, date: NaiveDate) -> bool {
+        let holiday = matches!((date.month(), date.day()), (12, 31) | (1, 1..=3));
+        let era = self.eras().iter().rev().find(|e| e.from <= date);
+        !holiday && era.is_some_and(|e| e.weekdays.contains(&date.weekday()))
+    }
+
+    /// First draw date on or after `date`.
+    pub fn next_draw_on_or_after(&self, date: NaiveDate) -> NaiveDate {
+        let mut d = date.max(self.eras()[0].from);
+        while !self.is_draw_day(d) {
+            d += Duration::days(1);
+        }
+        d
+    }
+
+    /// The next draw that has not started yet at `now`.
+    pub fn next_draw<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> NaiveDate {
+        let jst = now.with_timezone(&crate::oracle::DEFAULT_TZ);
+        let today = jst.date_naive();
+        if self.is_draw_day(today) && jst.time() < draw_time() {
+            today
+        } else {
+            self.next_draw_on_or_after(today + Duration::days(1))
+        }
+    }
+
+    /// Estimated draw number (回号) for a draw day, counted from draw #1 along the
+    /// schedule. Unscheduled cancellations would make this drift, hence "estimate".
+    pub fn estimate_draw_number(&self, date: NaiveDate) -> Option<u32> {
+        if !self.is_draw_day(date) {
+            return None;
+        }
+        let first = self.eras()[0].from;
+        let count = first
+            .iter_days()
+            .take_while(|d| *d <= date)
+            .filter(|d| self.is_draw_day(*d))
+            .count();
+        Some(count as u32)
+    }
+}
+
+/// The draw a batch of tickets is meant for.
+#[derive(Debug, Clone, Copy)]
+pub struct TargetDraw {
+    pub date: NaiveDate,
+    pub number: Option<u32>,
+}
+
+impl TargetDraw {
+    pub fn new(game: GameType, date: NaiveDate) -> Self {
+        TargetDraw {
+            date,
+            number: game.estimate_draw_number(date),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    #[test]
+    fn draw_weekdays() {
+        // 2024-02-12 is a Monday
+        assert!(GameType::Loto6.is_draw_day(d(2024, 2, 12)));
+        assert!(GameType::Loto6.is_draw_day(d(2024, 2, 15)));
+        assert!(!GameType::Loto6.is_draw_day(d(2024, 2, 16)));
+        assert!(GameType::Mini.is_draw_day(d(2024, 2, 13)));
+        assert!(GameType::Loto7.is_draw_day(d(2024, 2, 16)));
+        // Mondays only joined Loto6 in April 2011
+        assert!(!GameType::Loto6.is_draw_day(d(2011, 3, 28)));
+        // New Year holidays
+        assert!(!GameType::Loto7.is_draw_day(d(2021, 1, 1)));
+    }
+
+    #[test]
+    fn next_draw_respects_draw_time() {
+        let tz = crate::oracle::DEFAULT_TZ;
+        let before = tz.with_ymd_and_hms(2024, 2, 16, 18, 0, 0).unwrap();
+        let after = tz.with_ymd_and_hms(2024, 2, 16, 19, 0, 0).unwrap();
+        assert_eq!(GameType::Loto7.next_draw(&before), d(2024, 2, 16));
+        assert_eq!(GameType::Loto7.next_draw(&after), d(2024, 2, 23));
+        assert_eq!(GameType::Loto6.next_draw(&after), d(2024, 2, 19));
+        assert_eq!(GameType::Mini.next_draw(&after), d(2024, 2, 20));
+    }
+
+    #[test]
+    fn names_match_the_command_line() {
+        for game in [GameType::Loto6, GameType::Loto7, GameType::Mini] {
+            let value = game.to_possible_value().unwrap();
+            assert_eq!(value.get_name(), game.name());
+        }
+    }
+
+    #[test]
+    fn prize_tiers_follow_the_rules() {
+        assert_eq!(GameType::Loto6.prize_tier(6, 0), Some(1));
+        assert_eq!(GameType::Loto6.prize_tier(5, 1), Some(2));
+        assert_eq!(GameType::Loto6.prize_tier(5, 0), Some(3));
+        assert_eq!(GameType::Loto6.prize_tier(3, 1), Some(5));
+        assert_eq!(GameType::Loto6.prize_tier(2, 1), None);
+        assert_eq!(GameType::Loto7.prize_tier(6, 2), Some(2));
+        assert_eq!(GameType::Loto7.prize_tier(3, 1), Some(6));
+        assert_eq!(GameType::Loto7.prize_tier(3, 0), None);
+        assert_eq!(GameType::Mini.prize_tier(4, 1), Some(2));
+        assert_eq!(GameType::Mini.prize_tier(3, 0), Some(4));
+        assert_eq!(GameType::Mini.prize_tier(2, 1), None);
+
+        // Every tier has a reference prize, and the lowest is reachable
+        for game in [GameType::Loto6, GameType::Loto7, GameType::Mini] {
+            let picks = game.config().1;
+            let tiers = (0..=picks)
+                .flat_map(|m| (0..=game.bonus_count()).map(move |b| (m, b)))
+                .filter_map(|(m, b)| game.prize_tier(m, b))
+                .max();
+            assert_eq!(tiers, Some(game.reference_prizes().len() as u8));
+        }
+    }
+
+    #[test]
+    fn draw_numbers_count_from_the_first_draw() {
+        assert_eq!(GameType::Loto7.estimate_draw_number(d(2013, 4, 5)), Some(1));
+        assert_eq!(
+            GameType::Loto7.estimate_draw_number(d(2013, 4, 12)),
+            Some(2)
+        );
+        assert_eq!(
+            GameType::Loto6.estimate_draw_number(d(2000, 10, 5)),
+            Some(1)
+        );
+        assert_eq!(GameType::Loto6.estimate_draw_number(d(2024, 2, 13)), None);
+
+        let mon = GameType::Loto6
+            .estimate_draw_number(d(2024, 2, 12))
+            .unwrap();
+        let thu = GameType::Loto6
+            .estimate_draw_number(d(2024, 2, 15))
+            .unwrap();
+        assert_eq!(thu, mon + 1);
+    }
+}
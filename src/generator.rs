@@ -0,0 +1,288 @@
+use std::io;
+use std::ops::RangeInclusive;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+
+use crate::game::GameType;
+use crate::oracle::OracleDistribution;
+
+/// Tickets per RNG stream. Chunk `k` always draws from stream `k` of the seeded
+/// generator, so the output depends only on the seed, never on the thread count.
+pub const CHUNK_SIZE: usize = 4096;
+
+/// Side streams for Oracle rituals, counted down from the top of the stream space so
+/// they never meet a ticket chunk.
+pub const TAROT_STREAM: u64 = u64::MAX;
+pub const ICHING_STREAM: u64 = u64::MAX - 1;
+pub const CHAOS_STREAM: u64 = u64::MAX - 2;
+
+/// A generator for a ritual (tarot, ...) that replays with the run's seed.
+pub fn ritual_rng(seed: u64, stream: u64) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(stream);
+    rng
+}
+
+/// How the numbers of a run are chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Pure,
+    Spread,
+    Cluster,
+    Favorite,
+    Oracle,
+}
+
+impl Algorithm {
+    /// Name recorded in commitments.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Pure => "pure",
+            Algorithm::Spread => "spread",
+            Algorithm::Cluster => "cluster",
+            Algorithm::Favorite => "favorite",
+            Algorithm::Oracle => "oracle",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "pure" => Algorithm::Pure,
+            "spread" => Algorithm::Spread,
+            "cluster" => Algorithm::Cluster,
+            "favorite" => Algorithm::Favorite,
+            "oracle" | "divine" | "destiny" => {
+                eprintln!("🔮 The forbidden Oracle mode has been invoked. Probability bends, but math remains unchanged.");
+                Algorithm::Oracle
+            }
+            _ => Algorithm::Pure,
+        }
+    }
+
+    /// The sampler of a seeded algorithm, shared by ticket runs and `verify`;
+    /// `None` for the Oracle, which weighs its own distribution.
+    pub fn sampler(&self, game: GameType) -> Option<Sampler> {
+        let (range, picks) = game.config();
+        match self {
+            Algorithm::Oracle => None,
+            // TODO: spread / cluster / favorite still fall back to pure
+            Algorithm::Pure | Algorithm::Spread | Algorithm::Cluster | Algorithm::Favorite => {
+                Some(Sampler::Pure { range, picks })
+            }
+        }
+    }
+}
+
+/// How a single ticket is drawn.
+pub enum Sampler {
+    Pure {
+        range: RangeInclusive<u32>,
+        picks: usize,
+    },
+    Oracle(OracleDistribution),
+}
+
+impl Sampler {
+    pub fn picks(&self) -> usize {
+        match self {
+            Sampler::Pure { picks, .. } => *picks,
+            Sampler::Oracle(dist) => dist.count(),
+        }
+    }
+
+    /// Appends one sorted ticket to `out`.
+    fn sample_into<R: Rng>(&self, rng: &mut R, scratch: &mut Vec<u32>, out: &mut Vec<u32>) {
+        match self {
+            Sampler::Pure { range, picks } => {
+                if range.end() - range.start() < 64 {
+                    bitset_sample(range, *picks, rng, out);
+                } else {
+                    partial_shuffle_sample(range, *picks, rng, scratch, out);
+                }
+            }
+            Sampler::Oracle(dist) => out.extend(dist.sample(rng)),
+        }
+    }
+}
+
+/// Rejection sampling into a 64-bit set; bits come out already sorted.
+fn bitset_sample<R: Rng>(
+    range: &RangeInclusive<u32>,
+    picks: usize,
+    rng: &mut R,
+    out: &mut Vec<u32>,
+) {
+    let start = *range.start();
+    let span = range.end() - start + 1;
+    let mut bits = 0u64;
+    let mut taken = 0;
+    while taken < picks.min(span as usize) {
+        let bit = 1u64 << rng.gen_range(0..span);
+        if bits & bit == 0 {
+            bits |= bit;
+            taken += 1;
+        }
+    }
+    while bits != 0 {
+        out.push(start + bits.trailing_zeros());
+        bits &= bits - 1;
+    }
+}
+
+/// Partial Fisher–Yates: only the first `picks` positions are shuffled.
+fn partial_shuffle_sample<R: Rng>(
+    range: &RangeInclusive<u32>,
+    picks: usize,
+    rng: &mut R,
+    scratch: &mut Vec<u32>,
+    out: &mut Vec<u32>,
+) {
+    scratch.clear();
+    scratch.extend(range.clone());
+    let picks = picks.min(scratch.len());
+    for i in 0..picks {
+        let j = rng.gen_range(i..scratch.len());
+        scratch.swap(i, j);
+    }
+    let begin = out.len();
+    out.extend_from_slice(&scratch[..picks]);
+    out[begin..].sort_unstable();
+}
+
+/// Generates chunk `index` (tickets flattened, `picks` numbers each).
+fn generate_chunk(sampler: &Sampler, seed: u64, index: u64, tickets: usize) -> Vec<u32> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(index);
+    let mut scratch = Vec::new();
+    let mut out = Vec::with_capacity(tickets * sampler.picks());
+    for _ in 0..tickets {
+        sampler.sample_into(&mut rng, &mut scratch, &mut out);
+    }
+    out
+}
+
+/// Streams `n` tickets to `sink(draw_index, numbers)` in order, one chunk batch at a time.
+///
+/// With `threads > 1` each batch is generated on a rayon pool; the result is identical
+/// to the single-threaded run for the same seed.
+pub fn generate<F>(
+    sampler: &Sampler,
+    n: usize,
+    seed: u64,
+    threads: usize,
+    mut sink: F,
+) -> io::Result<()>
+where
+    F: FnMut(usize, &[u32]) -> io::Result<()>,
+{
+    let picks = sampler.picks();
+    let chunks = n.div_ceil(CHUNK_SIZE);
+    let chunk_len = |k: usize| CHUNK_SIZE.min(n - k * CHUNK_SIZE);
+
+    let pool = if threads == 1 {
+        None
+    } else {
+        Some(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(io::Error::other)?,
+        )
+    };
+    // Bound memory: only a few chunks per thread are in flight at once
+    let batch = pool.as_ref().map_or(1, |p| p.current_num_threads() * 4);
+
+    let mut draw_index = 1;
+    for first in (0..chunks).step_by(batch) {
+        let last = (first + batch).min(chunks);
+        let generated: Vec<Vec<u32>> = match &pool {
+            Some(pool) => pool.install(|| {
+                (first..last)
+                    .into_par_iter()
+                    .map(|k| generate_chunk(sampler, seed, k as u64, chunk_len(k)))
+                    .collect()
+            }),
+            None => (first..last)
+                .map(|k| generate_chunk(sampler, seed, k as u64, chunk_len(k)))
+                .collect(),
+        };
+
+        for chunk in generated {
+            for ticket in chunk.chunks_exact(picks) {
+                sink(draw_index, ticket)?;
+                draw_index += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(sampler: &Sampler, n: usize, seed: u64, threads: usize) -> Vec<Vec<u32>> {
+        let mut tickets = Vec::new();
+        generate(sampler, n, seed, threads, |i, t| {
+            assert_eq!(i, tickets.len() + 1);
+            tickets.push(t.to_vec());
+            Ok(())
+        })
+        .unwrap();
+        tickets
+    }
+
+    #[test]
+    fn same_seed_same_output_regardless_of_threads() {
+        let sampler = Sampler::Pure {
+            range: 1..=43,
+            picks: 6,
+        };
+        let n = CHUNK_SIZE * 3 + 17;
+        let serial = collect(&sampler, n, 2024, 1);
+        assert_eq!(serial.len(), n);
+        assert_eq!(serial, collect(&sampler, n, 2024, 4));
+        assert_ne!(serial, collect(&sampler, n, 2025, 1));
+    }
+
+    #[test]
+    fn tickets_are_sorted_distinct_and_in_range() {
+        for (range, picks) in [(1..=43, 6), (1..=37, 7), (1..=100, 5)] {
+            let sampler = Sampler::Pure {
+                range: range.clone(),
+                picks,
+            };
+            for ticket in collect(&sampler, 2000, 7, 1) {
+                assert_eq!(ticket.len(), picks);
+                assert!(ticket.windows(2).all(|w| w[0] < w[1]));
+                assert!(ticket.iter().all(|n| range.contains(n)));
+            }
+        }
+    }
+
+    #[test]
+    fn pure_sampling_is_uniform() {
+        let sampler = Sampler::Pure {
+            range: 1..=37,
+            picks: 7,
+        };
+        let n = 37_000;
+        let mut hits = [0usize; 38];
+        for ticket in collect(&sampler, n, 99, 1) {
+            for x in ticket {
+                hits[x as usize] += 1;
+            }
+        }
+        let expected = (n * 7 / 37) as f64;
+        for &h in &hits[1..] {
+            assert!(
+                (h as f64 - expected).abs() / expected < 0.05,
+                "{} vs {}",
+                h,
+                expected
+            );
+        }
+    }
+}
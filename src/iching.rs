@@ -0,0 +1,278 @@
+//! 易: hexagrams cast with the three-coin method.
+
+use rand::Rng;
+
+use crate::kyusei::Direction;
+
+/// The eight trigrams (八卦), in 先天 order (乾1 … 坤8).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigram {
+    Qian,
+    Dui,
+    Li,
+    Zhen,
+    Xun,
+    Kan,
+    Gen,
+    Kun,
+}
+
+impl Trigram {
+    pub const ALL: [Trigram; 8] = [
+        Trigram::Qian,
+        Trigram::Dui,
+        Trigram::Li,
+        Trigram::Zhen,
+        Trigram::Xun,
+        Trigram::Kan,
+        Trigram::Gen,
+        Trigram::Kun,
+    ];
+
+    /// Lines as bits, bottom line first (bit 0), yang = 1.
+    fn bits(&self) -> u8 {
+        match self {
+            Trigram::Qian => 0b111,
+            Trigram::Dui => 0b011,
+            Trigram::Li => 0b101,
+            Trigram::Zhen => 0b001,
+            Trigram::Xun => 0b110,
+            Trigram::Kan => 0b010,
+            Trigram::Gen => 0b100,
+            Trigram::Kun => 0b000,
+        }
+    }
+
+    fn from_bits(bits: u8) -> Trigram {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.bits() == bits & 0b111)
+            .unwrap_or(Trigram::Kun)
+    }
+
+    /// 先天八卦数: 乾1 兌2 離3 震4 巽5 坎6 艮7 坤8.
+    pub fn number(&self) -> u8 {
+        *self as u8 + 1
+    }
+
+    pub fn kanji(&self) -> &'static str {
+        match self {
+            Trigram::Qian => "乾",
+            Trigram::Dui => "兌",
+            Trigram::Li => "離",
+            Trigram::Zhen => "震",
+            Trigram::Xun => "巽",
+            Trigram::Kan => "坎",
+            Trigram::Gen => "艮",
+            Trigram::Kun => "坤",
+        }
+    }
+
+    /// Direction in the 後天 (King Wen) arrangement.
+    pub fn direction(&self) -> Direction {
+        match self {
+            Trigram::Kan => Direction::North,
+            Trigram::Gen => Direction::NorthEast,
+            Trigram::Zhen => Direction::East,
+            Trigram::Xun => Direction::SouthEast,
+            Trigram::Li => Direction::South,
+            Trigram::Kun => Direction::SouthWest,
+            Trigram::Dui => Direction::West,
+            Trigram::Qian => Direction::NorthWest,
+        }
+    }
+
+    pub fn image(&self) -> &'static str {
+        match self {
+            Trigram::Qian => "Heaven",
+            Trigram::Dui => "Lake",
+            Trigram::Li => "Fire",
+            Trigram::Zhen => "Thunder",
+            Trigram::Xun => "Wind",
+            Trigram::Kan => "Water",
+            Trigram::Gen => "Mountain",
+            Trigram::Kun => "Earth",
+        }
+    }
+
+    /// Row/column of the King Wen table: 乾 震 坎 艮 坤 巽 離 兌.
+    fn king_wen_index(&self) -> usize {
+        match self {
+            Trigram::Qian => 0,
+            Trigram::Zhen => 1,
+            Trigram::Kan => 2,
+            Trigram::Gen => 3,
+            Trigram::Kun => 4,
+            Trigram::Xun => 5,
+            Trigram::Li => 6,
+            Trigram::Dui => 7,
+        }
+    }
+}
+
+/// King Wen numbers, rows by lower trigram and columns by upper trigram,
+/// both in the order 乾 震 坎 艮 坤 巽 離 兌.
+#[rustfmt::skip]
+const KING_WEN: [[u8; 8]; 8] = [
+    [ 1, 34,  5, 26, 11,  9, 14, 43],
+    [25, 51,  3, 27, 24, 42, 21, 17],
+    [ 6, 40, 29,  4,  7, 59, 64, 47],
+    [33, 62, 39, 52, 15, 53, 56, 31],
+    [12, 16,  8, 23,  2, 20, 35, 45],
+    [44, 32, 48, 18, 46, 57, 50, 28],
+    [13, 55, 63, 22, 36, 37, 30, 49],
+    [10, 54, 60, 41, 19, 61, 38, 58],
+];
+
+const HEXAGRAM_NAMES: [&str; 64] = [
+    "乾", "坤", "屯", "蒙", "需", "訟", "師", "比", "小畜", "履", "泰", "否", "同人", "大有", "謙",
+    "豫", "随", "蠱", "臨", "観", "噬嗑", "賁", "剥", "復", "无妄", "大畜", "頤", "大過", "坎",
+    "離", "咸", "恒", "遯", "大壮", "晋", "明夷", "家人", "睽", "蹇", "解", "損", "益", "夬", "姤",
+    "萃", "升", "困", "井", "革", "鼎", "震", "艮", "漸", "帰妹", "豊", "旅", "巽", "兌", "渙",
+    "節", "中孚", "小過", "既済", "未済",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hexagram {
+    pub lower: Trigram,
+    pub upper: Trigram,
+}
+
+impl Hexagram {
+    /// Six lines as bits, bottom line first.
+    fn from_bits(bits: u8) -> Self {
+        Hexagram {
+            lower: Trigram::from_bits(bits),
+            upper: Trigram::from_bits(bits >> 3),
+        }
+    }
+
+    /// King Wen number, 1 – 64.
+    pub fn number(&self) -> u8 {
+        KING_WEN[self.lower.king_wen_index()][self.upper.king_wen_index()]
+    }
+
+    pub fn name(&self) -> &'static str {
+        HEXAGRAM_NAMES[self.number() as usize - 1]
+    }
+}
+
+impl std::fmt::Display for Hexagram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "#{} {} ({} {} over {} {})",
+            self.number(),
+            self.name(),
+            self.upper.kanji(),
+            self.upper.image(),
+            self.lower.kanji(),
+            self.lower.image()
+        )
+    }
+}
+
+/// Six line values from the coins, bottom first: 6 old yin, 7 young yang,
+/// 8 young yin, 9 old yang. Old lines are the changing ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cast {
+    pub lines: [u8; 6],
+}
+
+impl Cast {
+    /// Three coins per line, heads 3 and tails 2.
+    pub fn throw<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut lines = [0; 6];
+        for line in lines.iter_mut() {
+            *line = (0..3).map(|_| if rng.gen_bool(0.5) { 3 } else { 2 }).sum();
+        }
+        Cast { lines }
+    }
+
+    fn bits(&self, changed: bool) -> u8 {
+        self.lines.iter().enumerate().fold(0, |bits, (i, &v)| {
+            let yang = v % 2 == 1;
+            let flip = changed && (v == 6 || v == 9);
+            bits | (((yang != flip) as u8) << i)
+        })
+    }
+
+    pub fn primary(&self) -> Hexagram {
+        Hexagram::from_bits(self.bits(false))
+    }
+
+    /// The hexagram after the changing lines turn, if any do.
+    pub fn relating(&self) -> Option<Hexagram> {
+        self.lines
+            .iter()
+            .any(|&v| v == 6 || v == 9)
+            .then(|| Hexagram::from_bits(self.bits(true)))
+    }
+
+    /// Positions (1 = bottom) of the changing lines.
+    pub fn changing_lines(&self) -> Vec<usize> {
+        (1..=6)
+            .filter(|&i| matches!(self.lines[i - 1], 6 | 9))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn hexagram(lower: Trigram, upper: Trigram) -> Hexagram {
+        Hexagram { lower, upper }
+    }
+
+    #[test]
+    fn king_wen_numbers() {
+        use Trigram::*;
+        assert_eq!(hexagram(Qian, Qian).number(), 1);
+        assert_eq!(hexagram(Kun, Kun).number(), 2);
+        assert_eq!(hexagram(Qian, Kun).name(), "泰");
+        assert_eq!(hexagram(Kun, Qian).name(), "否");
+        assert_eq!(hexagram(Li, Kan).number(), 63);
+        assert_eq!(hexagram(Kan, Li).number(), 64);
+        assert_eq!(hexagram(Gen, Dui).number(), 31);
+    }
+
+    #[test]
+    fn every_hexagram_appears_once() {
+        let mut seen = [false; 65];
+        for bits in 0..64 {
+            let n = Hexagram::from_bits(bits).number() as usize;
+            assert!(!seen[n], "#{} twice", n);
+            seen[n] = true;
+        }
+        assert!(seen[1..].iter().all(|&s| s));
+    }
+
+    #[test]
+    fn changing_lines_make_the_relating_hexagram() {
+        // 復 (#24) with its single yang line moving turns into 坤 (#2)
+        let cast = Cast {
+            lines: [9, 8, 8, 8, 8, 8],
+        };
+        assert_eq!(cast.primary().number(), 24);
+        assert_eq!(cast.relating().unwrap().number(), 2);
+        assert_eq!(cast.changing_lines(), vec![1]);
+
+        let still = Cast {
+            lines: [7, 7, 7, 7, 7, 7],
+        };
+        assert_eq!(still.primary().number(), 1);
+        assert_eq!(still.relating(), None);
+    }
+
+    #[test]
+    fn coins_give_line_values() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for _ in 0..100 {
+            let cast = Cast::throw(&mut rng);
+            assert!(cast.lines.iter().all(|v| (6..=9).contains(v)));
+        }
+    }
+}
@@ -0,0 +1,452 @@
+//! 九星気学: the nine stars of the year, month and day, and the flying-star board.
+//!
+//! Years and months turn at 立春 and the 節 (see [`calendar::solar_month`]). Day stars
+//! ascend (陽遁) from the 甲子 day nearest the winter solstice and descend (陰遁) from
+//! the 甲子 day nearest the summer solstice.
+
+use chrono::{Duration, NaiveDate};
+use clap::ValueEnum;
+
+use crate::astro;
+use crate::calendar::{self, FiveElement};
+
+/// One of the nine stars, 1 = 一白水星 … 9 = 九紫火星.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Star(u8);
+
+const STAR_NAMES: [&str; 9] = [
+    "一白水星",
+    "二黒土星",
+    "三碧木星",
+    "四緑木星",
+    "五黄土星",
+    "六白金星",
+    "七赤金星",
+    "八白土星",
+    "九紫火星",
+];
+
+impl Star {
+    /// Wraps any integer onto 1..=9.
+    pub fn new(n: i64) -> Self {
+        Star(((n - 1).rem_euclid(9) + 1) as u8)
+    }
+
+    pub fn number(&self) -> u8 {
+        self.0
+    }
+
+    pub fn name(&self) -> &'static str {
+        STAR_NAMES[self.0 as usize - 1]
+    }
+
+    pub fn element(&self) -> FiveElement {
+        match self.0 {
+            1 => FiveElement::Water,
+            3 | 4 => FiveElement::Wood,
+            6 | 7 => FiveElement::Metal,
+            9 => FiveElement::Fire,
+            _ => FiveElement::Earth,
+        }
+    }
+
+    /// Year star of a solar-term year; 2000 was 九紫.
+    pub fn for_year(year: i32) -> Self {
+        Star::new(11 - year.rem_euclid(9) as i64)
+    }
+
+    /// 本命星: the year star of the birth date, with the year turning at 立春.
+    pub fn honmei(birth_date: NaiveDate) -> Self {
+        Star::for_year(calendar::solar_month(birth_date).0)
+    }
+
+    /// Month star: 寅月 opens on 八白, 五黄 or 二黒 depending on the year star,
+    /// then counts down.
+    pub fn for_month(date: NaiveDate) -> Self {
+        let (year, month) = calendar::solar_month(date);
+        let first = match Star::for_year(year).0 % 3 {
+            1 => 8, // 一白・四緑・七赤
+            2 => 2, // 二黒・五黄・八白
+            _ => 5, // 三碧・六白・九紫
+        };
+        Star::new(first - month)
+    }
+
+    /// Day star, counting up or down from the last 甲子 switch day.
+    pub fn for_day(date: NaiveDate) -> Self {
+        let (start, ascending) = last_switch(date);
+        let days = (date - start).num_days();
+        if ascending {
+            Star::new(1 + days)
+        } else {
+            Star::new(9 - days)
+        }
+    }
+
+    /// Whether `other` supports this star: same element (比和) or one that feeds or is
+    /// fed by it (相生). 五黄 befriends nobody.
+    pub fn is_friend(&self, other: Star) -> bool {
+        if self.0 == 5 || other.0 == 5 || *self == other {
+            return false;
+        }
+        let (a, b) = (self.element(), other.element());
+        a == b || a.generates() == b || b.generates() == a
+    }
+}
+
+impl std::fmt::Display for Star {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The eight directions, clockwise from north.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Direction {
+    North,
+    #[value(name = "northeast")]
+    NorthEast,
+    East,
+    #[value(name = "southeast")]
+    SouthEast,
+    South,
+    #[value(name = "southwest")]
+    SouthWest,
+    West,
+    #[value(name = "northwest")]
+    NorthWest,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// Position clockwise from north, 0 … 7.
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn opposite(&self) -> Direction {
+        Self::ALL[(self.index() + 4) % 8]
+    }
+
+    pub fn kanji(&self) -> &'static str {
+        match self {
+            Direction::North => "北",
+            Direction::NorthEast => "北東",
+            Direction::East => "東",
+            Direction::SouthEast => "南東",
+            Direction::South => "南",
+            Direction::SouthWest => "南西",
+            Direction::West => "西",
+            Direction::NorthWest => "北西",
+        }
+    }
+}
+
+/// 八宅 (Eight Mansions): how a direction sits with someone's 命卦.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mansion {
+    /// 生気
+    ShengQi,
+    /// 天医
+    TianYi,
+    /// 延年
+    YanNian,
+    /// 伏位
+    FuWei,
+    /// 禍害
+    HuoHai,
+    /// 五鬼
+    WuGui,
+    /// 六殺
+    LiuSha,
+    /// 絶命
+    JueMing,
+}
+
+impl Mansion {
+    const ORDER: [Mansion; 8] = [
+        Mansion::ShengQi,
+        Mansion::TianYi,
+        Mansion::YanNian,
+        Mansion::FuWei,
+        Mansion::HuoHai,
+        Mansion::WuGui,
+        Mansion::LiuSha,
+        Mansion::JueMing,
+    ];
+
+    pub fn kanji(&self) -> &'static str {
+        match self {
+            Mansion::ShengQi => "生気",
+            Mansion::TianYi => "天医",
+            Mansion::YanNian => "延年",
+            Mansion::FuWei => "伏位",
+            Mansion::HuoHai => "禍害",
+            Mansion::WuGui => "五鬼",
+            Mansion::LiuSha => "六殺",
+            Mansion::JueMing => "絶命",
+        }
+    }
+
+    pub fn is_auspicious(&self) -> bool {
+        matches!(
+            self,
+            Mansion::ShengQi | Mansion::TianYi | Mansion::YanNian | Mansion::FuWei
+        )
+    }
+}
+
+/// 八宅 directions per 命卦, in [`Mansion::ORDER`].
+#[rustfmt::skip]
+const MANSIONS: [(u8, [Direction; 8]); 8] = {
+    use Direction::*;
+    [
+        (1, [SouthEast, East, South, North, West, NorthEast, NorthWest, SouthWest]),
+        (2, [NorthEast, West, NorthWest, SouthWest, East, SouthEast, South, North]),
+        (3, [South, North, SouthEast, East, SouthWest, NorthWest, NorthEast, West]),
+        (4, [North, South, East, SouthEast, NorthWest, SouthWest, West, NorthEast]),
+        (6, [West, NorthEast, SouthWest, NorthWest, SouthEast, East, North, South]),
+        (7, [NorthWest, SouthWest, NorthEast, West, North, South, SouthEast, East]),
+        (8, [SouthWest, NorthWest, West, NorthEast, South, North, East, SouthEast]),
+        (9, [East, SouthEast, North, South, NorthEast, West, SouthWest, NorthWest]),
+    ]
+};
+
+/// 命卦 from the birth date (year turning at 立春) and gender. Men take the year star,
+/// women its mirror (6 − star); 五黄 has no trigram and becomes 坤 (2) for men and
+/// 艮 (8) for women.
+pub fn kua(birth_date: NaiveDate, female: bool) -> Star {
+    let star = Star::honmei(birth_date);
+    if female {
+        let kua = Star::new(6 - star.0 as i64);
+        if kua.0 == 5 {
+            Star(8)
+        } else {
+            kua
+        }
+    } else if star.0 == 5 {
+        Star(2)
+    } else {
+        star
+    }
+}
+
+/// Where `dir` stands for someone with this 命卦.
+pub fn mansion(kua: Star, dir: Direction) -> Mansion {
+    MANSIONS
+        .iter()
+        .find(|(k, _)| *k == kua.0)
+        .and_then(|(_, dirs)| dirs.iter().position(|d| *d == dir))
+        .map_or(Mansion::FuWei, |i| Mansion::ORDER[i])
+}
+
+/// Flying order of the 後天定位盤 after the center: 乾 兌 艮 離 坎 坤 震 巽.
+const FLIGHT: [Direction; 8] = [
+    Direction::NorthWest,
+    Direction::West,
+    Direction::NorthEast,
+    Direction::South,
+    Direction::North,
+    Direction::SouthWest,
+    Direction::East,
+    Direction::SouthEast,
+];
+
+/// A flying-star board (九星盤) with `center` in the middle.
+#[derive(Debug, Clone, Copy)]
+pub struct Board {
+    pub center: Star,
+}
+
+impl Board {
+    /// The star sitting in `dir`.
+    pub fn star_at(&self, dir: Direction) -> Star {
+        let step = FLIGHT.iter().position(|d| *d == dir).unwrap_or(0);
+        Star::new(self.center.0 as i64 + step as i64 + 1)
+    }
+
+    /// Where `star` sits, or `None` when it is in the center.
+    pub fn position_of(&self, star: Star) -> Option<Direction> {
+        Direction::ALL
+            .into_iter()
+            .find(|d| self.star_at(*d) == star)
+    }
+
+    /// 吉方位 for someone born under `honmei`: directions holding a friendly star,
+    /// minus 五黄殺, 暗剣殺, 本命殺 and 本命的殺.
+    pub fn lucky_directions(&self, honmei: Star) -> Vec<Direction> {
+        let mut bad = Vec::new();
+        if let Some(d) = self.position_of(Star(5)) {
+            bad.extend([d, d.opposite()]);
+        }
+        if let Some(d) = self.position_of(honmei) {
+            bad.extend([d, d.opposite()]);
+        }
+        Direction::ALL
+            .into_iter()
+            .filter(|d| !bad.contains(d) && honmei.is_friend(self.star_at(*d)))
+            .collect()
+    }
+}
+
+/// Nearest 甲子 day to `date` (ties go forward).
+fn nearest_kasshi(date: NaiveDate) -> NaiveDate {
+    let i = calendar::day_kanshi(date).index() as i64;
+    if i < 30 {
+        date - Duration::days(i)
+    } else {
+        date + Duration::days(60 - i)
+    }
+}
+
+/// JST date of the solstice with sun longitude `target` in `year`.
+fn solstice(year: i32, target: f64) -> NaiveDate {
+    let start = NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or_default();
+    let jd = astro::julian_day(calendar::jst_midnight(start));
+    calendar::jst_date(astro::next_sun_longitude(target, jd))
+}
+
+/// The latest 遁 switch on or before `date` and whether it ascends (陽遁).
+fn last_switch(date: NaiveDate) -> (NaiveDate, bool) {
+    use chrono::Datelike;
+    let mut switches = Vec::new();
+    for year in [date.year() - 1, date.year()] {
+        switches.push((nearest_kasshi(solstice(year, 90.0)), false));
+        switches.push((nearest_kasshi(solstice(year, 270.0)), true));
+    }
+    switches
+        .into_iter()
+        .filter(|(d, _)| *d <= date)
+        .max_by_key(|(d, _)| *d)
+        .unwrap_or((date, true))
+}
+
+/// The stars ruling a given day.
+#[derive(Debug, Clone, Copy)]
+pub struct KyuseiDay {
+    pub year: Star,
+    pub month: Star,
+    pub day: Star,
+}
+
+impl KyuseiDay {
+    pub fn for_date(date: NaiveDate) -> Self {
+        KyuseiDay {
+            year: Star::for_year(calendar::solar_month(date).0),
+            month: Star::for_month(date),
+            day: Star::for_day(date),
+        }
+    }
+
+    pub fn day_board(&self) -> Board {
+        Board { center: self.day }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    #[test]
+    fn year_stars() {
+        assert_eq!(Star::for_year(2024).number(), 3);
+        assert_eq!(Star::for_year(2000).number(), 9);
+        assert_eq!(Star::for_year(1990).number(), 1);
+        // 立春 boundary: early February 2024 still belongs to 2023 (四緑)
+        assert_eq!(Star::honmei(d(2024, 2, 3)).number(), 4);
+        assert_eq!(Star::honmei(d(2024, 2, 4)).number(), 3);
+    }
+
+    #[test]
+    fn month_stars() {
+        // 2024 is 三碧: 寅月 opens on 五黄
+        assert_eq!(Star::for_month(d(2024, 2, 10)).number(), 5);
+        assert_eq!(Star::for_month(d(2024, 3, 10)).number(), 4);
+        // January 2024 is 丑月 of the 四緑 year 2023: 八白 counted down eleven months
+        assert_eq!(Star::for_month(d(2024, 1, 10)).number(), 6);
+    }
+
+    #[test]
+    fn day_stars_follow_the_switches() {
+        // 2024-01-01 was a 甲子 day near the winter solstice: 陽遁 starts on 一白
+        assert_eq!(Star::for_day(d(2024, 1, 1)).number(), 1);
+        assert_eq!(Star::for_day(d(2024, 1, 2)).number(), 2);
+        assert_eq!(Star::for_day(d(2024, 1, 10)).number(), 1);
+        // Before the switch the old 陰遁 run still descends
+        let before = Star::for_day(d(2023, 12, 31)).number();
+        let earlier = Star::for_day(d(2023, 12, 30)).number();
+        assert_eq!(Star::new(earlier as i64 - 1).number(), before);
+    }
+
+    #[test]
+    fn kua_numbers() {
+        // 1990 (一白): men 1, women 8; 2000 (九紫): men 9, women 6
+        assert_eq!(kua(d(1990, 6, 1), false).number(), 1);
+        assert_eq!(kua(d(1990, 6, 1), true).number(), 8);
+        assert_eq!(kua(d(2000, 6, 1), false).number(), 9);
+        assert_eq!(kua(d(2000, 6, 1), true).number(), 6);
+        // 1985 (六白): men 6, women 9
+        assert_eq!(kua(d(1985, 6, 1), false).number(), 6);
+        assert_eq!(kua(d(1985, 6, 1), true).number(), 9);
+        // 五黄 falls back to a trigram: men born 1995 take 坤 (2), and women born
+        // 1990 (6 − 1 = 5) take 艮 (8) as above
+        assert_eq!(Star::for_year(1995).number(), 5);
+        assert_eq!(kua(d(1995, 6, 1), false).number(), 2);
+    }
+
+    #[test]
+    fn mansions() {
+        let one = Star(1);
+        assert_eq!(mansion(one, Direction::SouthEast), Mansion::ShengQi);
+        assert_eq!(mansion(one, Direction::North), Mansion::FuWei);
+        assert_eq!(mansion(one, Direction::SouthWest), Mansion::JueMing);
+        // East-group kua are at home in N, S, E and SE; west-group in the rest
+        for (kua, dirs) in MANSIONS {
+            let east = matches!(kua, 1 | 3 | 4 | 9);
+            for (i, dir) in dirs.iter().enumerate() {
+                let home = matches!(
+                    dir,
+                    Direction::North | Direction::South | Direction::East | Direction::SouthEast
+                );
+                assert_eq!(i < 4, home == east, "kua {} {:?}", kua, dir);
+            }
+        }
+    }
+
+    #[test]
+    fn board_positions() {
+        // 五黄 in the center is the 後天定位盤: 一白 north, 九紫 south
+        let board = Board { center: Star(5) };
+        assert_eq!(board.star_at(Direction::North).number(), 1);
+        assert_eq!(board.star_at(Direction::South).number(), 9);
+        assert_eq!(board.star_at(Direction::NorthWest).number(), 6);
+        assert_eq!(board.position_of(Star(5)), None);
+    }
+
+    #[test]
+    fn lucky_directions_avoid_the_killers() {
+        let board = Board { center: Star(1) };
+        let honmei = Star(6);
+        let lucky = board.lucky_directions(honmei);
+        let five = board.position_of(Star(5)).unwrap();
+        assert!(!lucky.contains(&five) && !lucky.contains(&five.opposite()));
+        for dir in &lucky {
+            assert!(honmei.is_friend(board.star_at(*dir)));
+        }
+        assert!(!lucky.is_empty());
+    }
+}
@@ -1,26 +1,55 @@
+mod astro;
+mod beacon;
+mod calendar;
+mod commit;
+mod config;
+mod game;
+mod generator;
+mod iching;
+mod kyusei;
 mod oracle;
 mod oracle_modules;
+mod oracle_sources;
+mod pool;
+mod profile;
+mod ritual;
+mod seimei;
+mod tarot;
 
 use std::error::Error;
 use std::fs::File;
-use std::io::Write;
-use std::ops::RangeInclusive;
+use std::io::{self, BufWriter, Write};
 
-use chrono::NaiveDate;
-use clap::{Parser, ValueEnum};
-use oracle::{AuraColor, BloodType, OracleContext, OracleEngine};
-use rand::seq::SliceRandom;
-use rand::thread_rng;
+use calendar::FourPillars;
+use chrono::{Datelike, NaiveDate, Utc};
+use chrono_tz::Tz;
+use clap::{Parser, Subcommand, ValueEnum};
+use commit::TicketSpec;
+use game::{GameType, TargetDraw};
+use generator::{Algorithm, Sampler};
+use kyusei::Direction;
+use oracle::{AuraColor, BloodType, Gender, LunarPhase, OracleContext, OracleEngine, Rokuyo};
+use oracle_sources::{FixedClock, FixedEntropy, FixedMetrics, MachineState};
+use pool::Pools;
+use profile::{Profile, Profiles};
+use rand::{thread_rng, Rng};
+use ritual::Ritual;
+use seimei::NameReading;
+use tarot::Spread;
 
 /// CLI 引数定義
 #[derive(Parser, Debug)]
 #[command(
     name = "loto-random-cli",
     version,
-    about = "ロト6 / ロト7 の完全ランダム数字ジェネレータ"
+    about = "ロト6 / ロト7 / ミニロト の完全ランダム数字ジェネレータ",
+    args_conflicts_with_subcommands = true
 )]
 struct Cli {
-    /// 種類: loto6 or loto7
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// 種類: loto6, loto7 or mini
     #[arg(value_enum, default_value_t = GameType::Loto6)]
     r#type: GameType,
 
@@ -36,6 +65,33 @@ struct Cli {
     #[arg(long)]
     out: Option<String>,
 
+    /// 乱数シード（指定すると同じ結果を再現できる）
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// キーを N 回押す間隔からシードを作る（省略時 32 回）。Oracle では共鳴値にもなる
+    #[arg(
+        long,
+        value_name = "PRESSES",
+        num_args = 0..=1,
+        default_missing_value = "32",
+        value_parser = clap::value_parser!(u16).range(8..),
+        conflicts_with_all = ["seed", "resonance"]
+    )]
+    ritual: Option<u16>,
+
+    /// 次回の抽選を対象にする（Oracle の日付も次回抽選日になり、CSV に回号を記録）
+    #[arg(long)]
+    for_next_draw: bool,
+
+    /// 生成前にシードと条件の SHA-256 コミットメントを表示（抽選後に verify で検証できる）
+    #[arg(long)]
+    commit: bool,
+
+    /// 生成スレッド数（0 = 全コア）。シードが同じなら結果はスレッド数に依存しない
+    #[arg(long, default_value_t = 1)]
+    threads: usize,
+
     // --- Oracle Mode Optionals ---
     /// 生年月日 (YYYY-MM-DD) - Oracle mode only
     #[arg(long)]
@@ -48,6 +104,237 @@ struct Cli {
     /// オーラカラー - Oracle mode only
     #[arg(long, value_enum)]
     aura_color: Option<AuraColorArg>,
+
+    /// 性別（八宅の命卦に使う）- Oracle mode only
+    #[arg(long, value_enum)]
+    gender: Option<GenderArg>,
+
+    /// 向いている方角（八宅で吉凶を見る）- Oracle mode only
+    #[arg(long, value_enum)]
+    facing: Option<Direction>,
+
+    /// 命卦の八方位すべての吉凶で数字を重み付け - Oracle mode only
+    #[arg(long)]
+    lucky_direction: bool,
+
+    /// 名前 ("姓 名" の漢字・かな、またはローマ字) - Oracle mode only
+    #[arg(long, value_parser = parse_name)]
+    name: Option<String>,
+
+    /// 保存したプロファイルを使う（省略時は `profile use` で選んだもの）- Oracle mode only
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// タロットのスプレッド - Oracle mode only
+    #[arg(long, value_enum, default_value_t = Spread::default())]
+    tarot_spread: Spread,
+
+    /// 占う日付 (YYYY-MM-DD、抽選時刻 18:45 として扱う) - Oracle mode only。省略時は現在
+    #[arg(long)]
+    date: Option<NaiveDate>,
+
+    /// 記録した観測者の共鳴値 (16進) で儀式を再現し、ENTER の入力を省く - Oracle mode only
+    #[arg(long, value_parser = parse_resonance)]
+    resonance: Option<u128>,
+
+    /// メモリ負荷 (%) だけを与える（ほかの計測は行わない）- Oracle mode only
+    #[arg(long, value_parser = oracle_sources::parse_load, conflicts_with_all = ["no_machine_spirit", "machine_state"])]
+    system_load: Option<f32>,
+
+    /// 表示・CSV に記録されたマシンの状態 (mem=…;cpu=…;up=…;procs=…;id=…) で再現する - Oracle mode only
+    #[arg(long, value_name = "RECORD", conflicts_with = "no_machine_spirit")]
+    machine_state: Option<MachineState>,
+
+    /// マシンの状態（負荷・稼働時間・マシンID）を読まない - Oracle mode only
+    #[arg(long)]
+    no_machine_spirit: bool,
+
+    /// 曜日・六曜・月相などを求めるタイムゾーン (IANA名) - Oracle mode only
+    #[arg(long, default_value_t = oracle::DEFAULT_TZ)]
+    tz: Tz,
+}
+
+/// サブコマンド（省略時はチケット生成）
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 旧暦の日付と六曜を表示
+    Calendar {
+        /// 対象日 (YYYY-MM-DD)。省略時は今日
+        #[arg(long)]
+        date: Option<NaiveDate>,
+    },
+    /// 算命学の命式（年柱・月柱・日柱）を表示
+    Fortune {
+        /// 生年月日 (YYYY-MM-DD)
+        #[arg(long)]
+        birth_date: NaiveDate,
+    },
+    /// 占いプロファイルの管理
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+    /// 共同購入（出資・チケット・当せん金の分配）の管理
+    Pool {
+        #[command(subcommand)]
+        action: PoolAction,
+    },
+    /// 16進のシード文字列（将来のブロックハッシュなど）から当選数字を決める模擬抽選
+    Draw {
+        /// 16進のシード文字列
+        #[arg(value_parser = parse_beacon)]
+        seed: String,
+        /// 種類: loto6, loto7 or mini
+        #[arg(value_enum, default_value_t = GameType::Loto6)]
+        r#type: GameType,
+    },
+    /// 公開したシードからチケットを再生成し、コミットメントと照合
+    #[command(alias = "reveal")]
+    Verify {
+        /// 事前に公開したコミットメント (SHA-256, 16進)
+        commitment: String,
+        /// 公開したシード
+        #[arg(long)]
+        seed: u64,
+        /// 種類: loto6, loto7 or mini
+        #[arg(value_enum, default_value_t = GameType::Loto6)]
+        r#type: GameType,
+        /// アルゴリズム（生成時と同じもの）
+        #[arg(default_value = "pure")]
+        algo: String,
+        /// 何口分生成したか
+        #[arg(long, default_value_t = 10)]
+        n: usize,
+        /// 手元のチケットCSV（--out で保存したもの）とも照合する
+        #[arg(long)]
+        tickets: Option<String>,
+    },
+}
+
+/// profile サブコマンド
+#[derive(Subcommand, Debug)]
+enum ProfileAction {
+    /// プロファイルを追加（同名があれば上書き）
+    Add {
+        /// プロファイル名（英数字・-・_）
+        key: String,
+        /// 生年月日 (YYYY-MM-DD)
+        #[arg(long)]
+        birth_date: Option<NaiveDate>,
+        /// 血液型
+        #[arg(long, value_enum)]
+        blood_type: Option<BloodTypeArg>,
+        /// オーラカラー
+        #[arg(long, value_enum)]
+        aura_color: Option<AuraColorArg>,
+        /// 名前 ("姓 名" またはローマ字)
+        #[arg(long, value_parser = parse_name)]
+        name: Option<String>,
+        /// 性別
+        #[arg(long, value_enum)]
+        gender: Option<GenderArg>,
+        /// お気に入りの数字（カンマ区切り、例: 7,21）
+        #[arg(long, value_delimiter = ',')]
+        favorites: Vec<u32>,
+    },
+    /// プロファイル一覧
+    List,
+    /// 既定のプロファイルにする
+    Use { key: String },
+    /// プロファイルを削除
+    Remove { key: String },
+}
+
+/// pool サブコマンド
+#[derive(Subcommand, Debug)]
+enum PoolAction {
+    /// プールを作る
+    Create {
+        /// プール名（英数字・-・_）
+        key: String,
+        /// 種類: loto6, loto7 or mini
+        #[arg(value_enum, default_value_t = GameType::Loto6)]
+        r#type: GameType,
+    },
+    /// メンバーの出資を記録（同じメンバーなら加算）。次の購入で使われる
+    Join {
+        key: String,
+        /// メンバー名
+        member: String,
+        /// 出資額（円）
+        yen: u64,
+    },
+    /// 次の購入に向けた出資を減らす（金額を省略すると全額を戻してプールから外す）
+    Leave {
+        key: String,
+        /// メンバー名
+        member: String,
+        /// 減らす金額（円）
+        yen: Option<u64>,
+    },
+    /// プール一覧、またはプールの詳細
+    Show { key: Option<String> },
+    /// 出資総額で買えるだけのチケットを生成し、対象の抽選回とともに記録
+    Tickets {
+        key: String,
+        /// この日以降の最初の抽選を対象にする (YYYY-MM-DD)。省略時は次回抽選
+        #[arg(long)]
+        date: Option<NaiveDate>,
+        /// 乱数シード
+        #[arg(long)]
+        seed: Option<u64>,
+        /// チケットをCSVにも書き出す
+        #[arg(long)]
+        out: Option<String>,
+        /// 当選結果の入力前のチケットを破棄し、その出資と合わせて買い直す
+        #[arg(long)]
+        replace: bool,
+    },
+    /// 当選結果の入力前の購入を取り消し、出資をプールに戻す
+    Cancel { key: String },
+    /// 当選数字から当せん金を計算し、出資額に比例して分配
+    Settle {
+        key: String,
+        /// 本数字（カンマ区切り）。省略時は記録済みの結果を使う（--prize はその金額を上書き）
+        #[arg(long, value_delimiter = ',', requires = "bonus")]
+        numbers: Vec<u32>,
+        /// ボーナス数字（カンマ区切り）
+        #[arg(long, value_delimiter = ',', requires = "numbers")]
+        bonus: Vec<u32>,
+        /// 等級ごとの1口の当せん金（例: 4=6800）。省略した等級は理論値の目安
+        #[arg(long, value_name = "TIER=YEN", value_parser = parse_prize)]
+        prize: Vec<(u8, u64)>,
+        /// 精算CSVの出力先
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// プールを削除
+    Remove { key: String },
+}
+
+/// --name の検証（姓名判断できる形か）
+fn parse_name(s: &str) -> Result<String, String> {
+    NameReading::parse(s).map(|_| s.trim().to_string())
+}
+
+fn parse_beacon(s: &str) -> Result<String, String> {
+    beacon::parse_beacon(s).map(|_| s.trim().to_string())
+}
+
+/// A prize override: `TIER=YEN`.
+fn parse_prize(s: &str) -> Result<(u8, u64), String> {
+    let (tier, yen) = s
+        .split_once('=')
+        .ok_or_else(|| format!("等級=金額 の形で指定してください: {}", s))?;
+    let tier = tier.trim().parse().map_err(|e| format!("等級: {}", e))?;
+    let yen = yen.trim().parse().map_err(|e| format!("金額: {}", e))?;
+    Ok((tier, yen))
+}
+
+/// The resonance as printed by the observer ritual, with or without `0x`.
+fn parse_resonance(s: &str) -> Result<u128, String> {
+    let digits = s.trim().trim_start_matches("0x");
+    u128::from_str_radix(digits, 16).map_err(|e| format!("16進数で指定してください: {}", e))
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -94,175 +381,665 @@ impl From<AuraColorArg> for AuraColor {
     }
 }
 
-/// ゲームタイプ
-#[derive(Copy, Clone, Debug, ValueEnum)]
-enum GameType {
-    Loto6,
-    Loto7,
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GenderArg {
+    Male,
+    Female,
 }
 
-impl GameType {
-    /// 各ゲームの数値範囲と口あたりの個数を返す
-    fn config(&self) -> (RangeInclusive<u32>, usize) {
-        match self {
-            GameType::Loto6 => (1..=43, 6),
-            GameType::Loto7 => (1..=37, 7),
+impl From<GenderArg> for Gender {
+    fn from(arg: GenderArg) -> Self {
+        match arg {
+            GenderArg::Male => Gender::Male,
+            GenderArg::Female => Gender::Female,
         }
     }
 }
 
-enum Algorithm {
-    Pure,
-    Spread,
-    Cluster,
-    Favorite,
-    Oracle,
+/// CSVヘッダ行を作る: draw,n1,n2,...,n6/7[,追加列...]
+fn build_header(picks: usize, extra: &[(&str, String)]) -> String {
+    let mut s = String::from("draw");
+    for i in 1..=picks {
+        s.push_str(&format!(",n{}", i));
+    }
+    for (name, _) in extra {
+        s.push(',');
+        s.push_str(name);
+    }
+    s.push('\n');
+    s
 }
 
-impl Algorithm {
-    fn from_str(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "pure" => Algorithm::Pure,
-            "spread" => Algorithm::Spread,
-            "cluster" => Algorithm::Cluster,
-            "favorite" => Algorithm::Favorite,
-            "oracle" | "divine" | "destiny" => {
-                eprintln!("🔮 The forbidden Oracle mode has been invoked. Probability bends, but math remains unchanged.");
-                Algorithm::Oracle
+/// 1行ぶんのCSV: 口番号 + 数字列 + 追加列（全行共通の値）
+fn write_row<W: Write>(
+    w: &mut W,
+    draw_index: usize,
+    numbers: &[u32],
+    extra: &[(&str, String)],
+) -> io::Result<()> {
+    write!(w, "{}", draw_index)?;
+    for n in numbers {
+        write!(w, ",{}", n)?;
+    }
+    for (_, value) in extra {
+        write!(w, ",{}", value)?;
+    }
+    writeln!(w)
+}
+
+/// 標準出力の1行: "01 , 02 , ..."
+fn write_line<W: Write>(w: &mut W, numbers: &[u32]) -> io::Result<()> {
+    for (i, n) in numbers.iter().enumerate() {
+        if i > 0 {
+            w.write_all(b" , ")?;
+        }
+        write!(w, "{:02}", n)?;
+    }
+    writeln!(w)
+}
+
+/// calendar サブコマンド: 旧暦・六曜・月相を表示
+fn run_calendar(date: NaiveDate) -> Result<(), Box<dyn Error>> {
+    let lunar = calendar::to_lunar(date).ok_or_else(|| {
+        format!(
+            "旧暦に変換できるのは {}年〜{}年 です: {}",
+            calendar::FIRST_YEAR,
+            calendar::LAST_YEAR,
+            date
+        )
+    })?;
+    let rokuyo = Rokuyo::from_lunar(&lunar);
+
+    // 月相は日本時間の正午で見る
+    let noon = calendar::jst_midnight(date) + chrono::Duration::hours(12);
+    let jd = astro::julian_day(noon);
+    let moon = LunarPhase::from_angle(astro::moon_elongation(jd));
+    let next_new = calendar::jst_date(astro::next_moon_elongation(0.0, jd));
+    let next_full = calendar::jst_date(astro::next_moon_elongation(180.0, jd));
+    let (term, term_date) = calendar::next_solar_term(jd);
+
+    println!("{} ({})", date, date.format("%a"));
+    println!("{}", lunar);
+    println!("六曜: {} ({:?})", rokuyo.kanji(), rokuyo);
+    println!(
+        "月相: {:?} (月齢 {:.1}, 輝面 {:.0}%)",
+        moon.phase,
+        moon.age_days(),
+        moon.illumination * 100.0
+    );
+    println!("次の新月: {} / 次の満月: {}", next_new, next_full);
+    println!("次の節気: {} ({})", term, term_date);
+    Ok(())
+}
+
+/// fortune サブコマンド: 四柱と日干・天中殺を表示
+fn run_fortune(birth_date: NaiveDate) -> Result<(), Box<dyn Error>> {
+    if !(calendar::FIRST_YEAR..=calendar::LAST_YEAR).contains(&birth_date.year()) {
+        return Err(format!(
+            "命式を出せるのは {}年〜{}年 です: {}",
+            calendar::FIRST_YEAR,
+            calendar::LAST_YEAR,
+            birth_date
+        )
+        .into());
+    }
+    let pillars = FourPillars::from_date(birth_date);
+    let void = pillars.void_branches();
+
+    println!("{} 生まれ", birth_date);
+    println!("年柱: {}", pillars.year);
+    println!("月柱: {}", pillars.month);
+    println!("日柱: {}", pillars.day);
+    println!(
+        "日干: {} ({})",
+        calendar::STEMS[pillars.day.stem() as usize],
+        pillars.day_master().kanji()
+    );
+    println!(
+        "天中殺: {}{}",
+        calendar::BRANCHES[void[0] as usize],
+        calendar::BRANCHES[void[1] as usize]
+    );
+    Ok(())
+}
+
+/// profile サブコマンド
+fn run_profile(action: ProfileAction) -> Result<(), Box<dyn Error>> {
+    let mut profiles = Profiles::load()?;
+    match action {
+        ProfileAction::Add {
+            key,
+            birth_date,
+            blood_type,
+            aura_color,
+            name,
+            gender,
+            favorites,
+        } => {
+            let profile = Profile {
+                birth_date,
+                blood_type: blood_type.map(|b| b.into()),
+                aura_color: aura_color.map(|a| a.into()),
+                name,
+                favorite_numbers: favorites,
+                gender: gender.map(|g| g.into()),
+            };
+            let replaced = profiles.insert(&key, profile)?;
+            let path = profiles.save()?;
+            println!(
+                "{} {} ({})",
+                if replaced {
+                    "更新しました:"
+                } else {
+                    "追加しました:"
+                },
+                key,
+                path.display()
+            );
+        }
+        ProfileAction::List => {
+            if profiles.profiles.is_empty() {
+                println!("プロファイルはまだありません (`profile add <名前>` で追加)");
             }
-            _ => Algorithm::Pure,
+            for (key, profile) in &profiles.profiles {
+                let mark = if profiles.default.as_deref() == Some(key) {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{} {}: {}", mark, key, profile.summary());
+            }
+        }
+        ProfileAction::Use { key } => {
+            profiles.set_default(&key)?;
+            profiles.save()?;
+            println!("既定のプロファイル: {}", key);
+        }
+        ProfileAction::Remove { key } => {
+            profiles.remove(&key)?;
+            profiles.save()?;
+            println!("削除しました: {}", key);
         }
     }
+    Ok(())
 }
 
-/// 1口分の番号を生成
-fn generate_ticket(
-    algo: &Algorithm,
-    range: RangeInclusive<u32>,
-    picks: usize,
-    oracle_engine: &mut Option<OracleEngine>,
-    oracle_ctx: &Option<OracleContext>,
-) -> Vec<u32> {
-    match algo {
-        Algorithm::Oracle => {
-            if let Some(engine) = oracle_engine {
-                if let Some(ctx) = oracle_ctx {
-                    return engine.divine(ctx);
+/// pool サブコマンド
+fn run_pool(action: PoolAction) -> Result<(), Box<dyn Error>> {
+    let mut pools = Pools::load()?;
+    match action {
+        PoolAction::Create { key, r#type } => {
+            pools.create(&key, r#type)?;
+            let path = pools.save()?;
+            println!(
+                "作成しました: {} {} ({})",
+                key,
+                r#type.label(),
+                path.display()
+            );
+        }
+        PoolAction::Join { key, member, yen } => {
+            let pool = pools.get_mut(&key)?;
+            let total = pool.contribute(&member, yen)?;
+            println!(
+                "{}: {}円 (合計 {}円 / 出資総額 {}円 = {}口)",
+                member.trim(),
+                yen,
+                total,
+                pool.budget(),
+                pool.ticket_count()
+            );
+            pools.save()?;
+        }
+        PoolAction::Leave { key, member, yen } => {
+            let pool = pools.get_mut(&key)?;
+            let left = pool.withdraw(&member, yen)?;
+            println!(
+                "{}: 残り {}円 / 出資総額 {}円 = {}口",
+                member.trim(),
+                left,
+                pool.budget(),
+                pool.ticket_count()
+            );
+            pools.save()?;
+        }
+        PoolAction::Cancel { key } => {
+            let pool = pools.get_mut(&key)?;
+            let draw = pool.cancel()?;
+            println!(
+                "取り消しました: {} {}口 / 出資総額 {}円",
+                describe_draw(&draw),
+                draw.tickets.len(),
+                pool.budget()
+            );
+            pools.save()?;
+        }
+        PoolAction::Show { key: None } => {
+            if pools.pools.is_empty() {
+                println!("プールはまだありません (`pool create <名前>` で作成)");
+            }
+            for (key, pool) in &pools.pools {
+                let status = match &pool.draw {
+                    None => "未購入".to_string(),
+                    Some(d) if d.result.is_some() => format!("{} 精算済み", d.date),
+                    Some(d) => format!("{} {}口購入", d.date, d.tickets.len()),
+                };
+                println!(
+                    "{}: {} / 次回出資 {}人 {}円 / {}",
+                    key,
+                    pool.game.label(),
+                    pool.members.len(),
+                    pool.budget(),
+                    status
+                );
+            }
+        }
+        PoolAction::Show { key: Some(key) } => {
+            let pool = pools.get(&key)?;
+            println!("{} ({})", key, pool.game.label());
+            for past in &pool.history {
+                let winnings = past
+                    .settlement(pool.game)
+                    .map_or(0, |settlement| settlement.winnings);
+                println!(
+                    "  過去の抽選: {} {}口 当せん金 {}円",
+                    describe_draw(past),
+                    past.tickets.len(),
+                    winnings
+                );
+            }
+            if let Some(draw) = &pool.draw {
+                println!("対象抽選: {} (seed {})", describe_draw(draw), draw.seed);
+                for (member, yen) in &draw.members {
+                    println!("  {}: {}円", member, yen);
+                }
+                let mut out = io::stdout().lock();
+                for ticket in &draw.tickets {
+                    write_line(&mut out, ticket)?;
                 }
             }
-            // Fallback if something went wrong
-            pure_ticket(range, picks)
+            if let Some(settlement) = pool.settlement() {
+                print_settlement(&settlement);
+            }
+            let pending = pool.draw.as_ref().is_some_and(|d| d.result.is_none());
+            if !pending || !pool.members.is_empty() {
+                println!("次回の出資:");
+                for (member, yen) in &pool.members {
+                    println!("  {}: {}円", member, yen);
+                }
+                println!(
+                    "出資総額 {}円 / 1口 {}円 → {}口",
+                    pool.budget(),
+                    pool.game.ticket_price(),
+                    pool.ticket_count()
+                );
+            }
+        }
+        PoolAction::Tickets {
+            key,
+            date,
+            seed,
+            out,
+            replace,
+        } => {
+            let pool = pools.get_mut(&key)?;
+            let game = pool.game;
+            let date = match date {
+                Some(d) => game.next_draw_on_or_after(d),
+                None => game.next_draw(&Utc::now()),
+            };
+            let seed = seed.unwrap_or_else(|| thread_rng().gen());
+            let draw = pool.buy(date, seed, replace)?;
+            eprintln!(
+                "🎯 対象抽選: {} {} / {}口 (seed {})",
+                game.label(),
+                describe_draw(draw),
+                draw.tickets.len(),
+                seed
+            );
+            let columns = [
+                (
+                    "target_draw",
+                    draw.number.map(|n| n.to_string()).unwrap_or_default(),
+                ),
+                ("draw_date", draw.date.to_string()),
+            ];
+            let mut stdout = io::stdout().lock();
+            for ticket in &draw.tickets {
+                write_line(&mut stdout, ticket)?;
+            }
+            if let Some(path) = &out {
+                let mut file = BufWriter::new(File::create(path)?);
+                file.write_all(build_header(game.config().1, &columns).as_bytes())?;
+                for (i, ticket) in draw.tickets.iter().enumerate() {
+                    write_row(&mut file, i + 1, ticket, &columns)?;
+                }
+                file.flush()?;
+            }
+            let leftover = draw.members.values().sum::<u64>() % game.ticket_price();
+            if leftover > 0 {
+                eprintln!("   {}円は1口に満たないため使われません", leftover);
+            }
+            pools.save()?;
+        }
+        PoolAction::Settle {
+            key,
+            numbers,
+            bonus,
+            prize,
+            out,
+        } => {
+            let pool = pools.get_mut(&key)?;
+            let settlement = if numbers.is_empty() {
+                pool.update_prizes(&prize.into_iter().collect())?
+            } else {
+                let mut main = numbers;
+                main.sort();
+                let winning = beacon::Drawing { main, bonus };
+                pool.enter_result(winning, &prize.into_iter().collect())?
+            };
+            pools.save()?;
+            print_settlement(&settlement);
+            if let Some(path) = &out {
+                std::fs::write(path, settlement.to_csv())?;
+                println!("精算CSV: {}", path);
+            }
+        }
+        PoolAction::Remove { key } => {
+            pools.remove(&key)?;
+            pools.save()?;
+            println!("削除しました: {}", key);
         }
-        Algorithm::Pure | _ => pure_ticket(range, picks),
-        // TODO: Implement other algos if needed, for now they fall back to pure or just placeholders
-        // We focus on Oracle.
     }
+    Ok(())
 }
 
-fn pure_ticket(range: RangeInclusive<u32>, picks: usize) -> Vec<u32> {
-    let mut nums: Vec<u32> = range.clone().collect();
-    let mut rng = thread_rng();
-    nums.shuffle(&mut rng);
-    nums.truncate(picks);
-    nums.sort();
-    nums
+/// "第N回(推定) YYYY-MM-DD"
+fn describe_draw(draw: &pool::PoolDraw) -> String {
+    format!(
+        "{} {}",
+        draw.number
+            .map(|n| format!("第{}回(推定)", n))
+            .unwrap_or_else(|| "回号不明".to_string()),
+        draw.date
+    )
 }
 
-/// CSVヘッダ行を作る: draw,n1,n2,...,n6/7
-fn build_header(picks: usize) -> String {
-    let mut s = String::from("draw");
-    for i in 1..=picks {
-        s.push_str(&format!(",n{}", i));
+fn print_settlement(settlement: &pool::Settlement) {
+    for win in &settlement.wins {
+        println!("  {}口目: {}等 {}円", win.ticket, win.tier, win.prize);
+    }
+    println!("当せん金合計: {}円", settlement.winnings);
+    for share in &settlement.shares {
+        println!(
+            "  {}: 出資 {}円 → {}円",
+            share.member, share.contribution, share.payout
+        );
     }
-    s.push('\n');
-    s
 }
 
-/// 1行ぶんのCSV: 口番号 + 数字列
-fn build_row(draw_index: usize, numbers: &[u32]) -> String {
-    let mut s = format!("{}", draw_index);
-    for n in numbers {
-        s.push(',');
-        s.push_str(&n.to_string());
+/// Only runs that the seed alone reproduces can be committed to.
+fn committable(algo: Algorithm) -> Result<Algorithm, String> {
+    match algo {
+        Algorithm::Oracle => Err(
+            "Oracle モードは観測（時刻・マシン・共鳴）に左右されるためコミットできません"
+                .to_string(),
+        ),
+        algo => Ok(algo),
     }
-    s.push('\n');
-    s
+}
+
+/// draw サブコマンド: ビーコンから本数字とボーナス数字を決める
+fn run_draw(game: GameType, seed: &str) -> Result<(), Box<dyn Error>> {
+    let drawing = beacon::draw(game, &beacon::parse_beacon(seed)?);
+    println!("{} 模擬抽選 (seed {})", game.label(), seed);
+    let mut out = io::stdout().lock();
+    write!(out, "本数字: ")?;
+    write_line(&mut out, &drawing.main)?;
+    write!(out, "ボーナス: ")?;
+    write_line(&mut out, &drawing.bonus)?;
+    Ok(())
+}
+
+/// verify サブコマンド: コミットメントを照合し、チケットを再生成して表示
+fn run_verify(
+    spec: &TicketSpec,
+    commitment: &str,
+    tickets_csv: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    if !spec.matches(commitment) {
+        return Err(format!(
+            "コミットメントが一致しません（この条件なら {}）",
+            spec.commitment()
+        )
+        .into());
+    }
+    println!("✅ コミットメント一致: {}", spec.commitment());
+    print!("{}", spec.canonical());
+
+    let tickets = spec.tickets()?;
+    let mut out = io::stdout().lock();
+    for ticket in &tickets {
+        write_line(&mut out, ticket)?;
+    }
+
+    if let Some(path) = tickets_csv {
+        let (_, picks) = spec.game.config();
+        let held = commit::read_tickets(&std::fs::read_to_string(path)?, picks)?;
+        if held != tickets {
+            let first = held
+                .iter()
+                .zip(&tickets)
+                .position(|(a, b)| a != b)
+                .unwrap_or(held.len().min(tickets.len()));
+            return Err(format!(
+                "{} のチケットが再生成結果と異なります（{}口目から / {}口 vs {}口）",
+                path,
+                first + 1,
+                held.len(),
+                tickets.len()
+            )
+            .into());
+        }
+        println!("✅ {} の {}口すべてが一致しました", path, held.len());
+    }
+    Ok(())
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let cli = Cli::parse();
+
+    if let Some(command) = cli.command {
+        return match command {
+            Command::Calendar { date } => {
+                run_calendar(date.unwrap_or_else(|| {
+                    oracle::oracle_moment(None, oracle::DEFAULT_TZ).date_naive()
+                }))
+            }
+            Command::Fortune { birth_date } => run_fortune(birth_date),
+            Command::Profile { action } => run_profile(action),
+            Command::Pool { action } => run_pool(action),
+            Command::Draw { seed, r#type } => run_draw(r#type, &seed),
+            Command::Verify {
+                commitment,
+                seed,
+                r#type,
+                algo,
+                n,
+                tickets,
+            } => {
+                let spec = TicketSpec {
+                    game: r#type,
+                    algo: committable(Algorithm::from_str(&algo))?,
+                    count: n,
+                    seed,
+                };
+                run_verify(&spec, &commitment, tickets.as_deref())
+            }
+        };
+    }
+
     let algo = Algorithm::from_str(&cli.algo);
 
-    let (range, picks) = cli.r#type.config();
+    let game = cli.r#type;
+    let (range, picks) = game.config();
     let max = *range.end();
 
-    // Prepare Oracle Engine if needed
-    let mut oracle_ctx = None;
-    let mut oracle_engine = None;
-
-    if let Algorithm::Oracle = algo {
-        // Mocking derived values for now; real implementation will come in modules
-        let ctx = OracleContext::from_args(
-            max,
-            picks as u32,
-            cli.birth_date,
-            cli.blood_type.map(|b| b.into()),
-            cli.aura_color.map(|a| a.into()),
+    // 対象の抽選回: --for-next-draw か、抽選日にあたる --date
+    let target = if cli.for_next_draw {
+        let date = match cli.date {
+            Some(d) => game.next_draw_on_or_after(d),
+            None => game.next_draw(&chrono::Utc::now()),
+        };
+        Some(TargetDraw::new(game, date))
+    } else {
+        cli.date
+            .filter(|d| game.is_draw_day(*d))
+            .map(|d| TargetDraw::new(game, d))
+    };
+
+    // CSV の追加列
+    let mut columns: Vec<(&str, String)> = Vec::new();
+    if let Some(t) = target {
+        eprintln!(
+            "🎯 対象抽選: {} {} {} ({})",
+            game.label(),
+            t.number
+                .map(|n| format!("第{}回(推定)", n))
+                .unwrap_or_else(|| "回号不明".to_string()),
+            t.date,
+            t.date.format("%a")
         );
-        oracle_ctx = Some(ctx);
+        columns.push((
+            "target_draw",
+            t.number.map(|n| n.to_string()).unwrap_or_default(),
+        ));
+        columns.push(("draw_date", t.date.to_string()));
     }
 
-    // Correction: OracleContext holding ThreadRng is problematic if we want to build it here.
-    // ThreadRng is thread-local handle.
-    // We should probably just instantiate it inside divine() or pass it.
-    // I will fix `oracle.rs` to NOT hold `rng: ThreadRng` in struct field if it causes issues,
-    // but actually `rand::ThreadRng` is just a handle, maybe it's fine?
-    // "ThreadRng does not implement Clone". So `rng.clone()` failed above.
-    // I'll leave `rng` out of `OracleContext` in the fix, or init it inside.
+    // The extended ritual contributes the seed (and the Oracle's resonance)
+    let ritual = cli
+        .ritual
+        .map(|presses| Ritual::perform(presses as usize))
+        .transpose()?;
+    if let Some(r) = &ritual {
+        let bits = r.min_entropy_bits();
+        eprintln!(
+            "🔑 Ritual complete: ~{:.0} bits of min-entropy gathered. Seed: {} (replay with --seed)",
+            bits,
+            r.seed()
+        );
+        if bits < 64.0 {
+            eprintln!("   The seed holds 64 bits; more keypresses would fill it.");
+        }
+    }
 
-    // For now, let's FIX oracle.rs before compiling main.rs fully?
-    // OR I can just fix the `OracleContext` initialization below to not clone, but if I need it multiple times...
-    // Actually, `divine` takes `&mut self` and `&OracleContext`.
-    // The modules need randomness.
-    // Best: `OracleContext` holds the seed or just allow modules to make their own `thread_rng()`.
-    // Modules creating their own `thread_rng()` is fine and easier.
+    // The seed drives the tickets and every Oracle ritual, so --seed replays both
+    let seed = cli
+        .seed
+        .or(ritual.as_ref().map(Ritual::seed))
+        .unwrap_or_else(|| thread_rng().gen());
 
-    // Let's remove `rng` from `OracleContext` in `oracle.rs` in next step.
+    if cli.commit {
+        let spec = TicketSpec {
+            game,
+            algo: committable(algo)?,
+            count: cli.n,
+            seed,
+        };
+        let commitment = spec.commitment();
+        eprintln!(
+            "🔒 Commitment (SHA-256): {} -> publish this before the draw.",
+            commitment
+        );
+        eprintln!(
+            "   Keep the seed {} secret until the results are in; then anyone can check with:",
+            seed
+        );
+        eprintln!("   {}", spec.verify_command());
+        columns.push(("commitment", commitment));
+    }
+
+    // Init Engine: weigh once, then every ticket samples the same distribution
+    let sampler = if let Some(sampler) = algo.sampler(game) {
+        sampler
+    } else {
+        // Flags on the command line win over the stored profile
+        let flags = Profile {
+            birth_date: cli.birth_date,
+            blood_type: cli.blood_type.map(|b| b.into()),
+            aura_color: cli.aura_color.map(|a| a.into()),
+            name: cli.name.clone(),
+            favorite_numbers: Vec::new(),
+            gender: cli.gender.map(|g| g.into()),
+        };
+        let seeker = match Profiles::for_run(cli.profile.as_deref())? {
+            Some((key, stored)) => {
+                eprintln!("👤 Profile: {}", key);
+                stored.overridden_by(flags)
+            }
+            None => flags,
+        };
+
+        let mut builder = OracleContext::builder(max, picks as u32)
+            .tz(cli.tz)
+            .seeker(seeker)
+            .seed(seed)
+            .tarot_spread(cli.tarot_spread)
+            .facing(cli.facing)
+            .lucky_direction(cli.lucky_direction);
+        if let Some(date) = target.map(|t| t.date).or(cli.date) {
+            let moment = oracle::oracle_moment(Some(date), cli.tz);
+            builder = builder.clock(FixedClock(moment.with_timezone(&Utc)));
+        }
+        if let Some(r) = &ritual {
+            builder = builder.entropy(FixedEntropy(Some(r.resonance())));
+        } else if cli.resonance.is_some() {
+            builder = builder.entropy(FixedEntropy(cli.resonance));
+        }
+        if let Some(machine) = cli.machine_state {
+            builder = builder.metrics(FixedMetrics(machine));
+        } else if cli.no_machine_spirit || cli.system_load.is_some() {
+            builder = builder.metrics(FixedMetrics(MachineState {
+                memory_load: cli.system_load,
+                ..MachineState::default()
+            }));
+        }
+        let ctx = builder.build();
+
+        let engine = OracleEngine::new(&ctx);
+        columns.extend(engine.columns(&ctx));
+        Sampler::Oracle(engine.weigh(&ctx))
+    };
 
     // out が指定されている場合だけ CSV を開く
     let mut csv_file = if let Some(path) = &cli.out {
-        Some(File::create(path)?)
+        Some(BufWriter::new(File::create(path)?))
     } else {
         None
     };
 
     // CSV があればヘッダを書く
     if let Some(file) = csv_file.as_mut() {
-        let header = build_header(picks);
+        let header = build_header(picks, &columns);
         file.write_all(header.as_bytes())?;
     }
 
-    // Init Engine
-    if let Some(ctx) = &oracle_ctx {
-        oracle_engine = Some(OracleEngine::new(ctx));
-    }
-
-    for i in 1..=cli.n {
-        let ticket = generate_ticket(&algo, range.clone(), picks, &mut oracle_engine, &oracle_ctx);
-
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    generator::generate(&sampler, cli.n, seed, cli.threads, |i, ticket| {
         // 標準出力
-        let line = ticket
-            .iter()
-            .map(|n| format!("{:02}", n))
-            .collect::<Vec<_>>()
-            .join(" , ");
-
-        println!("{}", line);
+        write_line(&mut out, ticket)?;
 
         if let Some(file) = csv_file.as_mut() {
-            let row = build_row(i, &ticket);
-            file.write_all(row.as_bytes())?;
+            write_row(file, i, ticket, &columns)?;
         }
+        Ok(())
+    })?;
+
+    out.flush()?;
+    if let Some(file) = csv_file.as_mut() {
+        file.flush()?;
     }
 
     Ok(())
@@ -1,17 +1,35 @@
-use chrono::{DateTime, NaiveDate, Utc};
+use crate::calendar::{self, FourPillars, LunarDate, Sexagenary};
+use crate::kyusei::{Direction, KyuseiDay, Star};
+use crate::oracle_sources::{
+    Clock, Entropy, HostMetrics, KeypressEntropy, MachineState, SystemClock, SystemMetrics,
+};
+use crate::profile::Profile;
+use crate::seimei::NameReading;
+use crate::tarot::Spread;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 // use rand::rngs::ThreadRng; // unused
 // use std::collections::HashMap; // unused
 
 // --- Enums for user input ---
-#[derive(Debug, Clone, Copy)]
+// Lowercase like the other enums; the aliases keep files written in upper case loading
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BloodType {
+    #[serde(alias = "A")]
     A,
+    #[serde(alias = "B")]
     B,
+    #[serde(alias = "O")]
     O,
+    #[serde(alias = "AB")]
     AB,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AuraColor {
     Red,
     Blue,
@@ -22,11 +40,18 @@ pub enum AuraColor {
     Black,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Gender {
+    Male,
+    Female,
+}
+
 pub use crate::oracle_modules::*;
 
 // --- Derived Astrological Enums ---
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WesternZodiac {
     Aries,
     Taurus,
@@ -42,6 +67,37 @@ pub enum WesternZodiac {
     Pisces,
 }
 
+impl WesternZodiac {
+    pub const ALL: [WesternZodiac; 12] = [
+        WesternZodiac::Aries,
+        WesternZodiac::Taurus,
+        WesternZodiac::Gemini,
+        WesternZodiac::Cancer,
+        WesternZodiac::Leo,
+        WesternZodiac::Virgo,
+        WesternZodiac::Libra,
+        WesternZodiac::Scorpio,
+        WesternZodiac::Sagittarius,
+        WesternZodiac::Capricorn,
+        WesternZodiac::Aquarius,
+        WesternZodiac::Pisces,
+    ];
+}
+
+/// How the birth-year animal meets the current year's animal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZodiacRelation {
+    /// 本命年: the animal's own year
+    Same,
+    /// 六合: a harmonious pair
+    Harmony,
+    /// 三合: members of the same trine
+    Trine,
+    /// 六冲: the opposite animal
+    Clash,
+    Neutral,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ChineseZodiac {
     Rat,
@@ -58,15 +114,105 @@ pub enum ChineseZodiac {
     Pig,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl ChineseZodiac {
+    pub const ALL: [ChineseZodiac; 12] = [
+        ChineseZodiac::Rat,
+        ChineseZodiac::Ox,
+        ChineseZodiac::Tiger,
+        ChineseZodiac::Rabbit,
+        ChineseZodiac::Dragon,
+        ChineseZodiac::Snake,
+        ChineseZodiac::Horse,
+        ChineseZodiac::Goat,
+        ChineseZodiac::Monkey,
+        ChineseZodiac::Rooster,
+        ChineseZodiac::Dog,
+        ChineseZodiac::Pig,
+    ];
+
+    /// Earthly branch index, 0 = 子 (Rat) … 11 = 亥 (Pig).
+    pub fn branch(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn from_branch(branch: u8) -> Self {
+        Self::ALL[branch as usize % 12]
+    }
+
+    pub fn relation_to(&self, other: ChineseZodiac) -> ZodiacRelation {
+        let (a, b) = (self.branch(), other.branch());
+        if a == b {
+            ZodiacRelation::Same
+        } else if (a + b) % 12 == 1 {
+            ZodiacRelation::Harmony
+        } else if a % 4 == b % 4 {
+            ZodiacRelation::Trine
+        } else if a.abs_diff(b) == 6 {
+            ZodiacRelation::Clash
+        } else {
+            ZodiacRelation::Neutral
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MoonPhase {
     New,
-    Waxing,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
     Full,
-    Waning,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+impl MoonPhase {
+    /// Eight 45° bins centred on new (0°), first quarter (90°), full (180°) and last quarter (270°).
+    pub fn from_angle(angle: f64) -> Self {
+        match (crate::astro::normalize_degrees(angle + 22.5) / 45.0) as u32 {
+            0 => MoonPhase::New,
+            1 => MoonPhase::WaxingCrescent,
+            2 => MoonPhase::FirstQuarter,
+            3 => MoonPhase::WaxingGibbous,
+            4 => MoonPhase::Full,
+            5 => MoonPhase::WaningGibbous,
+            6 => MoonPhase::LastQuarter,
+            _ => MoonPhase::WaningCrescent,
+        }
+    }
 }
 
+/// The Moon as seen at the Oracle's moment.
 #[derive(Debug, Clone, Copy)]
+pub struct LunarPhase {
+    /// Sun–Moon elongation in degrees: 0 new, 90 first quarter, 180 full, 270 last quarter.
+    pub angle: f64,
+    /// Illuminated fraction of the disc, 0.0 – 1.0.
+    pub illumination: f64,
+    pub phase: MoonPhase,
+}
+
+impl LunarPhase {
+    pub fn from_angle(angle: f64) -> Self {
+        LunarPhase {
+            angle,
+            illumination: crate::astro::illuminated_fraction(angle),
+            phase: MoonPhase::from_angle(angle),
+        }
+    }
+
+    /// Days since the last new moon.
+    pub fn age_days(&self) -> f64 {
+        self.angle / 360.0 * crate::astro::SYNODIC_MONTH
+    }
+
+    pub fn is_waxing(&self) -> bool {
+        self.angle < 180.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Rokuyo {
     Taian,
     Butsumetsu,
@@ -76,6 +222,31 @@ pub enum Rokuyo {
     Shakku,
 }
 
+impl Rokuyo {
+    /// (旧暦 month + day) % 6, counting 大安 as 0.
+    pub fn from_lunar(date: &LunarDate) -> Self {
+        match (date.month + date.day) % 6 {
+            0 => Rokuyo::Taian,
+            1 => Rokuyo::Shakku,
+            2 => Rokuyo::Senkatsu,
+            3 => Rokuyo::Tomobiki,
+            4 => Rokuyo::Senbu,
+            _ => Rokuyo::Butsumetsu,
+        }
+    }
+
+    pub fn kanji(&self) -> &'static str {
+        match self {
+            Rokuyo::Taian => "大安",
+            Rokuyo::Butsumetsu => "仏滅",
+            Rokuyo::Tomobiki => "友引",
+            Rokuyo::Senkatsu => "先勝",
+            Rokuyo::Senbu => "先負",
+            Rokuyo::Shakku => "赤口",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Weekday {
     Mon,
@@ -87,82 +258,106 @@ pub enum Weekday {
     Sun,
 }
 
+/// Oracle derivations follow Japan's calendar unless `--tz` says otherwise.
+pub const DEFAULT_TZ: Tz = chrono_tz::Asia::Tokyo;
+
+/// The instant the Oracle divines for: `date` at the draw time (18:45) in `tz`,
+/// or the current time when no date is given.
+pub fn oracle_moment(date: Option<NaiveDate>, tz: Tz) -> DateTime<Tz> {
+    let draw_time = crate::game::draw_time();
+    match date {
+        // `earliest` keeps DST gaps/overlaps from failing; JST has none
+        Some(d) => tz
+            .from_local_datetime(&d.and_time(draw_time))
+            .earliest()
+            .unwrap_or_else(|| tz.from_utc_datetime(&d.and_time(draw_time))),
+        None => SystemClock.now().with_timezone(&tz),
+    }
+}
+
 // --- Context and Engine ---
 #[derive(Debug)]
-#[allow(dead_code)]
 pub struct OracleContext {
     pub max: u32,
     pub count: u32,
-    pub now_utc: DateTime<Utc>,
+    pub now: DateTime<Tz>, // local time of the divination (JST by default)
 
     // User inputs
     pub birth_date: Option<NaiveDate>,
     pub blood_type: Option<BloodType>,
     pub aura_color: Option<AuraColor>,
+    pub name: Option<NameReading>,
+    pub favorite_numbers: Vec<u32>,
+    pub gender: Option<Gender>,
+    pub facing: Option<Direction>, // 八宅: the direction in question
+    pub lucky_direction: bool,     // 八宅: weigh all eight directions
+    pub tarot_spread: Spread,
 
     // System
     // rng is removed, modules should instantiate thread_rng() themselves or we pass it in methods
-    pub host_fingerprint: u64,
-    pub system_load: Option<f32>, // Memory usage percentage (0.0 - 100.0)
+    pub machine: MachineState,
     pub observer_resonance: Option<u128>, // Nanoseconds resonance
+    pub seed: u64,                        // The run's seed; rituals draw from side streams of it
 
     // Derived (computed in new())
     pub western_zodiac: Option<WesternZodiac>,
     pub chinese_zodiac: Option<ChineseZodiac>,
-    pub rokuyo: Rokuyo,
-    pub moon_phase: MoonPhase,
+    pub pillars: Option<FourPillars>, // 四柱 of the birth date
+    pub year_kanshi: Sexagenary,      // 干支 of the current lunar year
+    pub honmei_star: Option<Star>,    // 本命星
+    pub kyusei: KyuseiDay,            // 九星 of the current year, month and day
+    pub rokuyo: Option<Rokuyo>,       // None outside the 旧暦 table (1900–2100)
+    pub moon: LunarPhase,
+    #[allow(dead_code)] // no module reads the weekday yet
     pub weekday: Weekday,
 }
 
 impl OracleContext {
-    pub fn from_args(
-        max: u32,
-        count: u32,
-        birth_date: Option<NaiveDate>,
-        blood_type: Option<BloodType>,
-        aura_color: Option<AuraColor>,
-    ) -> Self {
-        use chrono::Datelike;
-        use std::io::{self, Write};
-        use std::time::{Instant, SystemTime, UNIX_EPOCH};
-        use sysinfo::System;
+    /// Starts a context for a game of `count` numbers out of `max`, observed through
+    /// the real clock, keyboard and machine unless told otherwise.
+    pub fn builder(max: u32, count: u32) -> OracleContextBuilder {
+        OracleContextBuilder {
+            max,
+            count,
+            tz: DEFAULT_TZ,
+            seeker: Profile::default(),
+            seed: None,
+            tarot_spread: Spread::default(),
+            facing: None,
+            lucky_direction: false,
+            clock: Box::new(SystemClock),
+            entropy: Box::new(KeypressEntropy),
+            metrics: Box::new(HostMetrics),
+        }
+    }
 
-        let now_utc = Utc::now();
+    /// The context for a seeker at `now`, without observing the machine or the user:
+    /// a silent machine, no resonance and a zero seed.
+    fn new(max: u32, count: u32, now: DateTime<Tz>, seeker: Profile) -> Self {
+        use chrono::Datelike;
 
-        // --- 1. Digital Animism (Machine Spirit) ---
-        let mut sys = System::new_all();
-        sys.refresh_all();
-        let total_mem = sys.total_memory();
-        let used_mem = sys.used_memory();
-        let system_load = if total_mem > 0 {
-            Some((used_mem as f32 / total_mem as f32) * 100.0)
-        } else {
-            None
-        };
+        let Profile {
+            birth_date,
+            blood_type,
+            aura_color,
+            name,
+            favorite_numbers,
+            gender,
+        } = seeker;
+        // Names were validated by the --name parser or when the profile was saved
+        let name = name.and_then(|n| NameReading::parse(&n).ok());
 
-        // --- 2. Quantum Observer Effect ---
-        eprintln!("🌌 Awaiting Observer Intervention...");
-        eprint!("   Press [ENTER] when you feel the cosmic alignment: ");
-        io::stdout().flush().unwrap();
-
-        let mut input = String::new();
-        let start = Instant::now();
-        io::stdin().read_line(&mut input).unwrap();
-        
-        let elapsed = start.elapsed().as_nanos();
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
-        let resonance = elapsed ^ timestamp; // XOR mixing
-        
-        eprintln!("⚡ Quantum state collapsed at {}ns. Resonance: {:x}", elapsed, resonance);
-
-        // Derivations
         let western_zodiac = birth_date.map(derive_western_zodiac);
-        let chinese_zodiac = birth_date.map(|d| derive_chinese_zodiac(d.year()));
-        let rokuyo = derive_rokuyo(now_utc);
-        let moon_phase = derive_moon_phase(now_utc);
-        
+        let chinese_zodiac = birth_date.map(derive_chinese_zodiac);
+        let pillars = birth_date.map(FourPillars::from_date);
+        let honmei_star = birth_date.map(Star::honmei);
+        let kyusei = KyuseiDay::for_date(now.date_naive());
+        let year_kanshi = Sexagenary::for_year(calendar::lunar_year(now.date_naive()));
+        let rokuyo = derive_rokuyo(now.date_naive());
+        let moon = derive_moon_phase(now.with_timezone(&Utc));
+
         // Weekday
-        let weekday = match now_utc.weekday() {
+        let weekday = match now.weekday() {
             chrono::Weekday::Mon => Weekday::Mon,
             chrono::Weekday::Tue => Weekday::Tue,
             chrono::Weekday::Wed => Weekday::Wed,
@@ -172,28 +367,119 @@ impl OracleContext {
             chrono::Weekday::Sun => Weekday::Sun,
         };
 
-        // Pseudo fingerprint mixed with resonance
-        let host_fingerprint = 0xCAFEBABE ^ (resonance as u64); 
-
         OracleContext {
             max,
             count,
-            now_utc,
+            now,
             birth_date,
             blood_type,
             aura_color,
-            host_fingerprint,
-            system_load,
-            observer_resonance: Some(resonance),
+            name,
+            favorite_numbers,
+            gender,
+            facing: None,
+            lucky_direction: false,
+            tarot_spread: Spread::default(),
+            machine: MachineState::default(),
+            observer_resonance: None,
+            seed: 0,
             western_zodiac,
             chinese_zodiac,
+            pillars,
+            year_kanshi,
+            honmei_star,
+            kyusei,
             rokuyo,
-            moon_phase,
+            moon,
             weekday,
         }
     }
 }
 
+pub struct OracleContextBuilder {
+    max: u32,
+    count: u32,
+    tz: Tz,
+    seeker: Profile,
+    seed: Option<u64>,
+    tarot_spread: Spread,
+    facing: Option<Direction>,
+    lucky_direction: bool,
+    clock: Box<dyn Clock>,
+    entropy: Box<dyn Entropy>,
+    metrics: Box<dyn SystemMetrics>,
+}
+
+impl OracleContextBuilder {
+    pub fn tz(mut self, tz: Tz) -> Self {
+        self.tz = tz;
+        self
+    }
+
+    pub fn seeker(mut self, seeker: Profile) -> Self {
+        self.seeker = seeker;
+        self
+    }
+
+    /// The run's seed; the rituals draw from side streams of it. Random if not given.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn tarot_spread(mut self, spread: Spread) -> Self {
+        self.tarot_spread = spread;
+        self
+    }
+
+    pub fn facing(mut self, facing: Option<Direction>) -> Self {
+        self.facing = facing;
+        self
+    }
+
+    pub fn lucky_direction(mut self, lucky_direction: bool) -> Self {
+        self.lucky_direction = lucky_direction;
+        self
+    }
+
+    /// The clock fixes the moment of divination (see [`oracle_moment`] for a draw date).
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    pub fn entropy(mut self, entropy: impl Entropy + 'static) -> Self {
+        self.entropy = Box::new(entropy);
+        self
+    }
+
+    pub fn metrics(mut self, metrics: impl SystemMetrics + 'static) -> Self {
+        self.metrics = Box::new(metrics);
+        self
+    }
+
+    /// Observes the machine and the observer, then derives the rest.
+    pub fn build(mut self) -> OracleContext {
+        let now = self.clock.now().with_timezone(&self.tz);
+        eprintln!("🕰️  Oracle moment: {}", now.format("%Y-%m-%d %H:%M %Z"));
+
+        // --- 1. Digital Animism (Machine Spirit) ---
+        let machine = self.metrics.read();
+
+        // --- 2. Quantum Observer Effect ---
+        let resonance = self.entropy.resonance();
+
+        let mut ctx = OracleContext::new(self.max, self.count, now, self.seeker);
+        ctx.machine = machine;
+        ctx.observer_resonance = resonance;
+        ctx.seed = self.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        ctx.tarot_spread = self.tarot_spread;
+        ctx.facing = self.facing;
+        ctx.lucky_direction = self.lucky_direction;
+        ctx
+    }
+}
+
 // Helpers
 fn derive_western_zodiac(d: NaiveDate) -> WesternZodiac {
     use chrono::Datelike;
@@ -287,53 +573,65 @@ fn derive_western_zodiac(d: NaiveDate) -> WesternZodiac {
     }
 }
 
-fn derive_chinese_zodiac(year: i32) -> ChineseZodiac {
-    match (year - 4) % 12 {
-        0 => ChineseZodiac::Rat,
-        1 => ChineseZodiac::Ox,
-        2 => ChineseZodiac::Tiger,
-        3 => ChineseZodiac::Rabbit,
-        4 => ChineseZodiac::Dragon,
-        5 => ChineseZodiac::Snake,
-        6 => ChineseZodiac::Horse,
-        7 => ChineseZodiac::Goat,
-        8 => ChineseZodiac::Monkey,
-        9 => ChineseZodiac::Rooster,
-        10 => ChineseZodiac::Dog,
-        _ => ChineseZodiac::Pig,
-    }
+/// The animal of the lunar year (starting at 旧正月, not January 1st).
+fn derive_chinese_zodiac(date: NaiveDate) -> ChineseZodiac {
+    ChineseZodiac::from_branch(Sexagenary::for_year(calendar::lunar_year(date)).branch())
 }
 
-fn derive_rokuyo(now: DateTime<Utc>) -> Rokuyo {
-    use chrono::Datelike;
-    // Mock: just use day of month
-    match now.day() % 6 {
-        0 => Rokuyo::Taian,
-        1 => Rokuyo::Butsumetsu,
-        2 => Rokuyo::Tomobiki,
-        3 => Rokuyo::Senkatsu,
-        4 => Rokuyo::Senbu,
-        _ => Rokuyo::Shakku,
-    }
+fn derive_rokuyo(local_date: NaiveDate) -> Option<Rokuyo> {
+    calendar::to_lunar(local_date).map(|d| Rokuyo::from_lunar(&d))
 }
 
-fn derive_moon_phase(now: DateTime<Utc>) -> MoonPhase {
-    use chrono::Datelike;
-    // Mock: sync with 30 day cycle roughly
-    let day = now.day() % 30; // 0..29
-    if day < 7 {
-        MoonPhase::New
-    } else if day < 15 {
-        MoonPhase::Waxing
-    } else if day < 22 {
-        MoonPhase::Full
-    } else {
-        MoonPhase::Waning
-    }
+fn derive_moon_phase(now: DateTime<Utc>) -> LunarPhase {
+    let jd = crate::astro::julian_day(now);
+    LunarPhase::from_angle(crate::astro::moon_elongation(jd))
+}
+
+/// Draws `count` distinct 0-based indices from `weights` without replacement.
+///
+/// Uses Efraimidis–Spirakis keys (`u^(1/w)`, keep the largest), which is equivalent to
+/// drawing one index at a time and re-weighting the remainder, so inclusion probabilities
+/// are exact and the running time does not depend on how skewed the weights are.
+/// Indices weighted `+inf` outweigh everything else and are chosen first (uniformly among
+/// themselves). Negative or NaN weights are treated as zero, and zero-weight indices are
+/// only chosen (uniformly) once every positively weighted index has been taken.
+pub fn weighted_sample_without_replacement<R: Rng + ?Sized>(
+    weights: &[f64],
+    count: usize,
+    rng: &mut R,
+) -> Vec<usize> {
+    // (tier, key): infinite weights rank above positive ones, which rank above zero
+    // weights, then by key
+    let mut keyed: Vec<(u8, f64, usize)> = weights
+        .iter()
+        .enumerate()
+        .map(|(idx, &w)| {
+            let u: f64 = 1.0 - rng.gen::<f64>(); // (0, 1]
+            if w == f64::INFINITY {
+                (2, u, idx)
+            } else if w > 0.0 {
+                (1, u.ln() / w, idx)
+            } else {
+                (0, u, idx)
+            }
+        })
+        .collect();
+
+    keyed.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(b.1.total_cmp(&a.1)));
+    keyed
+        .into_iter()
+        .take(count)
+        .map(|(_, _, idx)| idx)
+        .collect()
 }
 
 pub trait DivinationModule {
     fn apply(&self, ctx: &OracleContext, weights: &mut [f64]);
+
+    /// Extra CSV columns recording what the module saw (e.g. the cards drawn).
+    fn columns(&self, _ctx: &OracleContext) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
 }
 
 pub struct OracleEngine {
@@ -342,33 +640,56 @@ pub struct OracleEngine {
 
 impl OracleEngine {
     pub fn new(_ctx: &OracleContext) -> Self {
-        let mut modules: Vec<Box<dyn DivinationModule>> = vec![];
-
-        // Register modules
-        // 1. Western Astrology
-        modules.push(Box::new(WesternAstrology));
-        // 2. Chinese Zodiac
-        modules.push(Box::new(ChineseZodiacModule));
-        // 3. Sanmei
-        modules.push(Box::new(SanmeiModule));
-        // 4. Moon Phase
-        modules.push(Box::new(MoonPhaseModule));
-        // 5. Rokuyo
-        modules.push(Box::new(RokuyoModule));
-        // 6. Feng Shui
-        modules.push(Box::new(FengShuiModule));
-        // 7. Blood Type
-        modules.push(Box::new(BloodTypeModule));
-        // 8. Chaos
-        modules.push(Box::new(ChaosModule));
-        // 9. Stats
-        modules.push(Box::new(StatsModule));
-
-        // Return engine
-        Self { modules }
-    }
-
-    pub fn divine(&mut self, ctx: &OracleContext) -> Vec<u32> {
+        Self {
+            modules: Self::modules(WesternRules::load()),
+        }
+    }
+
+    fn modules(western_rules: WesternRules) -> Vec<Box<dyn DivinationModule>> {
+        vec![
+            // 1. Western Astrology
+            Box::new(WesternAstrology::new(western_rules)),
+            // 2. Chinese Zodiac
+            Box::new(ChineseZodiacModule),
+            // 3. Sanmei
+            Box::new(SanmeiModule),
+            // 4. Kyusei Kigaku
+            Box::new(KyuseiModule),
+            // 5. Moon Phase
+            Box::new(MoonPhaseModule),
+            // 6. Rokuyo
+            Box::new(RokuyoModule),
+            // 7. Feng Shui
+            Box::new(FengShuiModule),
+            // 8. Blood Type
+            Box::new(BloodTypeModule),
+            // 9. Chaos
+            Box::new(ChaosModule),
+            // 10. Stats
+            Box::new(StatsModule),
+            // 11. Name (姓名判断)
+            Box::new(NameModule),
+            // 12. Tarot
+            Box::new(TarotModule),
+            // 13. I Ching
+            Box::new(IChingModule),
+            // 14. Favorites
+            Box::new(FavoritesModule),
+            // 15. Machine Spirit
+            Box::new(MachineSpiritModule),
+            // 16. Observer Resonance
+            Box::new(ResonanceModule),
+        ]
+    }
+
+    /// CSV columns contributed by the modules, in registration order.
+    pub fn columns(&self, ctx: &OracleContext) -> Vec<(&'static str, String)> {
+        self.modules.iter().flat_map(|m| m.columns(ctx)).collect()
+    }
+
+    /// Weigh phase: runs every module once and returns the resulting distribution.
+    /// The context does not change during a run, so this only needs to happen once.
+    pub fn weigh(&self, ctx: &OracleContext) -> OracleDistribution {
         let range_len = ctx.max as usize;
         let mut weights = vec![1.0; range_len + 1]; // 1-based index (0 unused)
 
@@ -391,37 +712,22 @@ impl OracleEngine {
             }
         }
 
-        // Weighted sampling
-        use rand::distributions::WeightedIndex;
-        use rand::prelude::*;
-
-        // Build WeightedIndex from weights[1..] (since 0 is unused)
-        // We need to map indices back to numbers 1..=max
-        let valid_weights: Vec<f64> = weights.into_iter().skip(1).collect();
-
-        let mut result = Vec::new();
-        let mut rng = rand::thread_rng();
-
-        // Simple weighted sampling without replacement
-        // Note: WeightedIndex is immutable, so for without-replacement we might need
-        // to reject duplicates or re-build distribution.
-        // For small 'count', rejection sampling (check if exists) is fine.
-
-        let dist = WeightedIndex::new(&valid_weights).unwrap();
-
-        while result.len() < ctx.count as usize {
-            let idx = dist.sample(&mut rng);
-            let number = (idx + 1) as u32; // 0-index -> 1-based number
-
-            if !result.contains(&number) {
-                result.push(number);
-            }
+        // Numbers 1..=max map to indices 0..max from here on
+        let weights: Vec<f64> = weights.into_iter().skip(1).collect();
+        if !weights.iter().any(|w| w.is_finite() && *w > 0.0) {
+            eprintln!(
+                "⚠️  The heavens are silent (all weights vanished); falling back to uniform fate."
+            );
         }
 
-        result.sort();
+        let dist = OracleDistribution {
+            weights,
+            count: ctx.count as usize,
+        };
+
         eprintln!(
             "✨ REVELATION (啓示): [{}]",
-            result
+            dist.favored()
                 .iter()
                 .map(|n| n.to_string())
                 .collect::<Vec<_>>()
@@ -429,6 +735,614 @@ impl OracleEngine {
         );
         eprintln!("(Disclaimer: This is still just biased randomness. The universe laughs in expected value.)");
 
+        dist
+    }
+}
+
+/// Per-number weights produced by [`OracleEngine::weigh`]; sampling it is cheap.
+#[derive(Debug, Clone)]
+pub struct OracleDistribution {
+    weights: Vec<f64>, // index i -> number i + 1
+    count: usize,
+}
+
+impl OracleDistribution {
+    /// Sample phase: draws one ticket (sorted, distinct numbers).
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<u32> {
+        let mut result: Vec<u32> =
+            weighted_sample_without_replacement(&self.weights, self.count, rng)
+                .into_iter()
+                .map(|idx| (idx + 1) as u32) // 0-index -> 1-based number
+                .collect();
+        result.sort();
+        result
+    }
+
+    /// Numbers per ticket.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The `count` most heavily weighted numbers, sorted.
+    pub fn favored(&self) -> Vec<u32> {
+        let mut order: Vec<usize> = (0..self.weights.len()).collect();
+        order.sort_by(|&a, &b| self.weights[b].total_cmp(&self.weights[a]));
+        let mut result: Vec<u32> = order
+            .into_iter()
+            .take(self.count)
+            .map(|idx| (idx + 1) as u32)
+            .collect();
+        result.sort();
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn inclusion_frequencies(weights: &[f64], count: usize, trials: usize) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut hits = vec![0usize; weights.len()];
+        for _ in 0..trials {
+            let picked = weighted_sample_without_replacement(weights, count, &mut rng);
+            assert_eq!(picked.len(), count);
+            for idx in picked {
+                hits[idx] += 1;
+            }
+        }
+        hits.iter().map(|&h| h as f64 / trials as f64).collect()
+    }
+
+    #[test]
+    fn single_pick_follows_weights() {
+        let freq = inclusion_frequencies(&[1.0, 2.0, 3.0, 4.0], 1, 100_000);
+        for (f, expected) in freq.iter().zip([0.1, 0.2, 0.3, 0.4]) {
+            assert!((f - expected).abs() < 0.01, "{} vs {}", f, expected);
+        }
+    }
+
+    #[test]
+    fn two_picks_match_successive_sampling() {
+        // P(3) = 1/2 + 2 * 1/4 * 2/3 = 5/6, P(1) = P(2) = (2 - 5/6) / 2 = 7/12
+        let freq = inclusion_frequencies(&[1.0, 1.0, 2.0], 2, 100_000);
+        for (f, expected) in freq.iter().zip([7.0 / 12.0, 7.0 / 12.0, 5.0 / 6.0]) {
+            assert!((f - expected).abs() < 0.01, "{} vs {}", f, expected);
+        }
+    }
+
+    #[test]
+    fn dominant_weights_still_yield_distinct_picks() {
+        let mut weights = vec![1e-12; 43];
+        weights[0] = 1e12;
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut picked = weighted_sample_without_replacement(&weights, 6, &mut rng);
+        assert_eq!(picked[0], 0);
+        picked.sort();
+        picked.dedup();
+        assert_eq!(picked.len(), 6);
+    }
+
+    #[test]
+    fn degenerate_weights_fall_back_to_uniform() {
+        let freq = inclusion_frequencies(&[0.0, f64::NAN, -1.0, f64::NEG_INFINITY], 2, 100_000);
+        for f in freq {
+            assert!((f - 0.5).abs() < 0.01, "{}", f);
+        }
+    }
+
+    #[test]
+    fn infinite_weights_are_taken_first() {
+        let freq = inclusion_frequencies(&[1.0, f64::INFINITY, 1e300, f64::INFINITY], 2, 20_000);
+        assert_eq!(freq, vec![0.0, 1.0, 0.0, 1.0]);
+
+        // Beyond the infinite ones, the rest is drawn as usual
+        let freq = inclusion_frequencies(&[f64::INFINITY, 0.0, 1.0, 3.0], 2, 100_000);
+        assert_eq!(freq[0], 1.0);
+        assert_eq!(freq[1], 0.0);
+        assert!((freq[3] - 0.75).abs() < 0.01, "{}", freq[3]);
+    }
+
+    #[test]
+    fn zero_weights_are_taken_last() {
+        let freq = inclusion_frequencies(&[0.0, 5.0, 0.0, 1.0], 3, 20_000);
+        assert_eq!(freq[1], 1.0);
+        assert_eq!(freq[3], 1.0);
+        assert!((freq[0] - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn distribution_samples_sorted_distinct_numbers() {
+        let dist = OracleDistribution {
+            weights: vec![1.0, 0.0, 3.0, 2.0, 1.0],
+            count: 3,
+        };
+        assert_eq!(dist.favored(), vec![1, 3, 4]);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let ticket = dist.sample(&mut rng);
+            assert_eq!(ticket.len(), 3);
+            assert!(ticket.windows(2).all(|w| w[0] < w[1]));
+            assert!(!ticket.contains(&2));
+        }
+    }
+
+    /// Throughput check for bulk Oracle runs: `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bulk_sampling_throughput() {
+        let dist = OracleDistribution {
+            weights: (1..=43).map(|i| 1.0 + (i % 7) as f64 / 10.0).collect(),
+            count: 6,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let n = 1_000_000;
+        let start = std::time::Instant::now();
+        let mut checksum = 0u64;
+        for _ in 0..n {
+            checksum += dist.sample(&mut rng)[0] as u64;
+        }
+        let secs = start.elapsed().as_secs_f64();
+        eprintln!(
+            "{} tickets in {:.3}s ({:.0} tickets/s, checksum {})",
+            n,
+            secs,
+            n as f64 / secs,
+            checksum
+        );
+    }
+
+    #[test]
+    fn moon_phase_bins() {
+        assert_eq!(MoonPhase::from_angle(0.0), MoonPhase::New);
+        assert_eq!(MoonPhase::from_angle(350.0), MoonPhase::New);
+        assert_eq!(MoonPhase::from_angle(30.0), MoonPhase::WaxingCrescent);
+        assert_eq!(MoonPhase::from_angle(90.0), MoonPhase::FirstQuarter);
+        assert_eq!(MoonPhase::from_angle(180.0), MoonPhase::Full);
+        assert_eq!(MoonPhase::from_angle(270.0), MoonPhase::LastQuarter);
+        assert_eq!(MoonPhase::from_angle(315.0), MoonPhase::WaningCrescent);
+    }
+
+    #[test]
+    fn moon_phase_on_known_dates() {
+        use chrono::TimeZone;
+        // Full moon 2024-01-25 17:54 UTC, new moon 2024-02-09 22:59 UTC
+        let full = derive_moon_phase(Utc.with_ymd_and_hms(2024, 1, 25, 12, 0, 0).unwrap());
+        assert_eq!(full.phase, MoonPhase::Full);
+        assert!(full.illumination > 0.99);
+        let new = derive_moon_phase(Utc.with_ymd_and_hms(2024, 2, 10, 0, 0, 0).unwrap());
+        assert_eq!(new.phase, MoonPhase::New);
+        assert!(new.illumination < 0.01);
+        assert!(new.age_days() < 1.0);
+    }
+
+    #[test]
+    fn rokuyo_from_lunar_calendar() {
+        let rokuyo = |y, m, d| {
+            calendar::to_lunar(NaiveDate::from_ymd_opt(y, m, d).unwrap())
+                .map(|l| Rokuyo::from_lunar(&l))
+        };
+        // 旧暦 1/1 is always 先勝
+        assert_eq!(rokuyo(2024, 2, 10), Some(Rokuyo::Senkatsu));
+        // 2024-01-01 = 旧暦 11/20 -> 赤口
+        assert_eq!(rokuyo(2024, 1, 1), Some(Rokuyo::Shakku));
+        assert_eq!(rokuyo(2024, 1, 2), Some(Rokuyo::Senkatsu));
+        assert_eq!(rokuyo(2150, 1, 1), None);
+    }
+
+    #[test]
+    fn oracle_moment_uses_local_draw_time() {
+        use chrono::{Datelike, Timelike};
+        let date = NaiveDate::from_ymd_opt(2024, 2, 10).unwrap();
+        let jst = oracle_moment(Some(date), DEFAULT_TZ);
+        assert_eq!((jst.day(), jst.hour(), jst.minute()), (10, 18, 45));
+        assert_eq!(jst.with_timezone(&Utc).hour(), 9);
+
+        let utc = oracle_moment(Some(date), chrono_tz::UTC);
+        assert_eq!(utc.date_naive(), date);
+    }
+
+    #[test]
+    fn early_morning_jst_is_already_the_next_day() {
+        // 2024-02-09 20:00 UTC is 2024-02-10 05:00 JST: 旧暦 1/1 (先勝) in Japan,
+        // but still 12/30 (大安) by the UTC calendar
+        let instant = Utc.with_ymd_and_hms(2024, 2, 9, 20, 0, 0).unwrap();
+        let jst = instant.with_timezone(&DEFAULT_TZ);
+        assert_eq!(derive_rokuyo(jst.date_naive()), Some(Rokuyo::Senkatsu));
+        assert_eq!(derive_rokuyo(instant.date_naive()), Some(Rokuyo::Taian));
+    }
+
+    #[test]
+    fn chinese_zodiac_follows_lunar_new_year() {
+        // 旧正月 1990 fell on January 27th
+        let d = |y, m, day| NaiveDate::from_ymd_opt(y, m, day).unwrap();
+        assert_eq!(derive_chinese_zodiac(d(1990, 1, 20)), ChineseZodiac::Snake);
+        assert_eq!(derive_chinese_zodiac(d(1990, 2, 1)), ChineseZodiac::Horse);
+        assert_eq!(derive_chinese_zodiac(d(2024, 2, 10)), ChineseZodiac::Dragon);
+    }
+
+    #[test]
+    fn zodiac_relations() {
+        use ChineseZodiac::*;
+        assert_eq!(Rat.relation_to(Rat), ZodiacRelation::Same);
+        assert_eq!(Rat.relation_to(Ox), ZodiacRelation::Harmony);
+        assert_eq!(Tiger.relation_to(Pig), ZodiacRelation::Harmony);
+        assert_eq!(Horse.relation_to(Goat), ZodiacRelation::Harmony);
+        assert_eq!(Rat.relation_to(Dragon), ZodiacRelation::Trine);
+        assert_eq!(Monkey.relation_to(Rat), ZodiacRelation::Trine);
+        assert_eq!(Rat.relation_to(Horse), ZodiacRelation::Clash);
+        assert_eq!(Snake.relation_to(Pig), ZodiacRelation::Clash);
+        assert_eq!(Rat.relation_to(Tiger), ZodiacRelation::Neutral);
+    }
+
+    // --- Module harness: every module over every game's range ---
+
+    /// (max, count) of Loto6, Loto7 and Mini.
+    const GAMES: [(u32, u32); 3] = [(43, 6), (37, 7), (31, 5)];
+
+    const BLOOD_TYPES: [BloodType; 4] = [BloodType::A, BloodType::B, BloodType::O, BloodType::AB];
+
+    const AURAS: [AuraColor; 7] = [
+        AuraColor::Red,
+        AuraColor::Blue,
+        AuraColor::Green,
+        AuraColor::Gold,
+        AuraColor::Purple,
+        AuraColor::White,
+        AuraColor::Black,
+    ];
+
+    fn jst(y: i32, m: u32, d: u32) -> DateTime<Tz> {
+        DEFAULT_TZ.with_ymd_and_hms(y, m, d, 18, 45, 0).unwrap()
+    }
+
+    /// A seeker who told the oracle everything.
+    fn full_seeker() -> Profile {
+        Profile {
+            birth_date: NaiveDate::from_ymd_opt(1990, 6, 1),
+            blood_type: Some(BloodType::A),
+            aura_color: Some(AuraColor::Red),
+            name: Some("山田 太郎".to_string()),
+            favorite_numbers: vec![7, 21],
+            gender: Some(Gender::Female),
+        }
+    }
+
+    fn modules() -> Vec<Box<dyn DivinationModule>> {
+        OracleEngine::modules(WesternRules::builtin())
+    }
+
+    /// Runs one module on fresh weights and checks they stay usable.
+    fn apply(module: &dyn DivinationModule, ctx: &OracleContext) -> Vec<f64> {
+        let mut weights = vec![1.0; ctx.max as usize + 1];
+        module.apply(ctx, &mut weights);
+        assert_eq!(weights[0], 1.0, "index 0 is not a number");
+        for (i, w) in weights.iter().enumerate().skip(1) {
+            assert!(w.is_finite() && *w > 0.0, "weight of {} is {}", i, w);
+        }
+        weights
+    }
+
+    /// Numbers whose weight the module raised.
+    fn boosted(weights: &[f64]) -> Vec<usize> {
+        (1..weights.len()).filter(|&i| weights[i] > 1.0).collect()
+    }
+
+    #[test]
+    fn context_needs_no_observer() {
+        let ctx = OracleContext::new(43, 6, jst(2024, 2, 10), full_seeker());
+        assert_eq!(ctx.observer_resonance, None);
+        assert!(ctx.machine.is_silent());
+        assert_eq!(ctx.rokuyo, Some(Rokuyo::Senkatsu));
+        assert_eq!(ctx.western_zodiac, Some(WesternZodiac::Gemini));
+        assert_eq!(ctx.chinese_zodiac, Some(ChineseZodiac::Horse));
+        assert!(ctx.name.is_some());
+    }
+
+    #[test]
+    fn builder_replays_fixed_observations() {
+        use crate::oracle_sources::{FixedClock, FixedEntropy, FixedMetrics};
+        let build = || {
+            OracleContext::builder(43, 6)
+                .seeker(full_seeker())
+                .seed(7)
+                .facing(Some(Direction::South))
+                .clock(FixedClock(
+                    Utc.with_ymd_and_hms(2024, 2, 10, 9, 45, 0).unwrap(),
+                ))
+                .entropy(FixedEntropy(Some(0xABCD)))
+                .metrics(FixedMetrics(MachineState {
+                    memory_load: Some(42.0),
+                    ..MachineState::default()
+                }))
+                .build()
+        };
+        let ctx = build();
+        assert_eq!(ctx.now, jst(2024, 2, 10));
+        assert_eq!(ctx.observer_resonance, Some(0xABCD));
+        assert_eq!(ctx.machine.memory_load, Some(42.0));
+        assert_eq!((ctx.seed, ctx.facing), (7, Some(Direction::South)));
+        assert_eq!(apply(&TarotModule, &ctx), apply(&TarotModule, &build()));
+
+        // Every module, Chaos included, reads only the fixed sources and the seed
+        let engine = OracleEngine { modules: modules() };
+        let first = engine.weigh(&ctx).weights;
+        assert_eq!(first, engine.weigh(&build()).weights);
+        assert_ne!(
+            first,
+            engine.weigh(&OracleContext { seed: 8, ..ctx }).weights
+        );
+    }
+
+    #[test]
+    fn every_module_keeps_weights_positive_on_every_game() {
+        let modules = modules();
+        let start = jst(2024, 1, 1);
+        // Two months cover every 六曜, moon phase and weekday
+        for day in 0..60u32 {
+            let now = start + chrono::Duration::days(day as i64);
+            let i = day as usize;
+            let seeker = Profile {
+                birth_date: NaiveDate::from_ymd_opt(
+                    1950 + i as i32,
+                    1 + i as u32 % 12,
+                    1 + i as u32 % 28,
+                ),
+                blood_type: Some(BLOOD_TYPES[i % 4]),
+                aura_color: Some(AURAS[i % 7]),
+                gender: Some(if i.is_multiple_of(2) {
+                    Gender::Male
+                } else {
+                    Gender::Female
+                }),
+                ..full_seeker()
+            };
+            for (max, count) in GAMES {
+                for seeker in [Profile::default(), seeker.clone()] {
+                    let mut ctx = OracleContext::new(max, count, now, seeker);
+                    ctx.seed = day as u64;
+                    ctx.observer_resonance = Some(day as u128 * 0x1_0000_0001);
+                    ctx.machine = MachineState {
+                        memory_load: Some(day as f32 * 5.0 % 101.0),
+                        cpu_load: Some(100.0 - day as f32 % 101.0),
+                        uptime_secs: Some(day as u64 * 40_000),
+                        process_count: Some(i * 7),
+                        fingerprint: Some((day as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)),
+                    };
+                    ctx.facing = Some(Direction::ALL[i % 8]);
+                    ctx.lucky_direction = i.is_multiple_of(3);
+                    for module in &modules {
+                        apply(module.as_ref(), &ctx);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn modules_survive_tiny_ranges() {
+        let modules = modules();
+        for max in 1..=12 {
+            for (i, &blood) in BLOOD_TYPES.iter().enumerate() {
+                let seeker = Profile {
+                    blood_type: Some(blood),
+                    aura_color: Some(AURAS[i]),
+                    ..full_seeker()
+                };
+                let mut ctx = OracleContext::new(max, 1, jst(2024, 2, 10), seeker);
+                ctx.lucky_direction = true;
+                for module in &modules {
+                    apply(module.as_ref(), &ctx);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn blood_types_boost_their_numbers() {
+        let expected: [(BloodType, [Vec<usize>; 3]); 4] = [
+            (
+                BloodType::A,
+                [
+                    (14..=28).collect(),
+                    (12..=24).collect(),
+                    (10..=20).collect(),
+                ],
+            ),
+            (
+                BloodType::B,
+                [
+                    (1..=4).chain(39..=43).collect(),
+                    (1..=4).chain(33..=37).collect(),
+                    (1..=4).chain(27..=31).collect(),
+                ],
+            ),
+            (
+                BloodType::O,
+                [
+                    (21..=43).collect(),
+                    (18..=37).collect(),
+                    (15..=31).collect(),
+                ],
+            ),
+            (
+                BloodType::AB,
+                [vec![11, 22, 33], vec![11, 22, 33], vec![11, 22]],
+            ),
+        ];
+        for (blood, per_game) in expected {
+            for ((max, count), numbers) in GAMES.into_iter().zip(per_game) {
+                let seeker = Profile {
+                    blood_type: Some(blood),
+                    ..Profile::default()
+                };
+                let ctx = OracleContext::new(max, count, jst(2024, 2, 10), seeker);
+                let weights = apply(&BloodTypeModule, &ctx);
+                assert_eq!(boosted(&weights), numbers, "{:?} on {}", blood, max);
+            }
+        }
+    }
+
+    #[test]
+    fn calendar_modules_boost_their_numbers() {
+        // 2024-02-10: 旧暦 1/1 (先勝), a new moon
+        for (max, count) in GAMES {
+            let ctx = OracleContext::new(max, count, jst(2024, 2, 10), Profile::default());
+            let weights = apply(&RokuyoModule, &ctx);
+            assert_eq!(
+                boosted(&weights),
+                (1..(max as usize).div_ceil(2)).collect::<Vec<_>>()
+            );
+
+            // The dark moon favors the low end
+            let weights = apply(&MoonPhaseModule, &ctx);
+            assert!(weights[1] > weights[max as usize]);
+
+            // Day 10, month 2: 10, 2 and 12 are hot
+            let weights = apply(&StatsModule, &ctx);
+            assert_eq!(boosted(&weights), vec![2, 10, 12]);
+
+            // The day star's digital root
+            let weights = apply(&KyuseiModule, &ctx);
+            let root = ctx.kyusei.day.number() as usize;
+            let day_star: Vec<usize> = (1..=max as usize)
+                .filter(|&i| (i - 1) % 9 + 1 == root)
+                .collect();
+            assert!(!day_star.is_empty());
+            assert_eq!(boosted(&weights), day_star);
+        }
+    }
+
+    #[test]
+    fn seeker_modules_boost_their_numbers() {
+        for (max, count) in GAMES {
+            let mut ctx = OracleContext::new(max, count, jst(2024, 2, 10), full_seeker());
+
+            let weights = apply(&FavoritesModule, &ctx);
+            assert_eq!(boosted(&weights), vec![7, 21]);
+
+            // 山田 太郎: 天格 8, 人格 9, 地格 13, 総格 21 (all auspicious)
+            let weights = apply(&NameModule, &ctx);
+            for n in [8, 9, 13, 21] {
+                assert!(weights[n] >= 1.3, "{} on {}", n, max);
+            }
+
+            // Red: Fire quadrant plus the South eighth; no 八宅 without --facing
+            let weights = apply(&FengShuiModule, &ctx);
+            let fire: Vec<usize> = (1..=max as usize)
+                .filter(|&i| (i - 1) * 4 / max as usize == 2)
+                .collect();
+            assert_eq!(boosted(&weights), fire);
+
+            // 1990 woman: 命卦 8, whose 生気 is SouthWest
+            ctx.aura_color = None;
+            ctx.facing = Some(Direction::SouthWest);
+            let weights = apply(&FengShuiModule, &ctx);
+            let southwest: Vec<usize> = (1..=max as usize)
+                .filter(|&i| (i - 1) * 8 / max as usize == Direction::SouthWest.index())
+                .collect();
+            assert_eq!(boosted(&weights), southwest);
+        }
+    }
+
+    #[test]
+    fn birth_modules_boost_their_numbers() {
+        for (max, count) in GAMES {
+            let ctx = OracleContext::new(max, count, jst(2024, 2, 10), full_seeker());
+            let range_len = max as usize;
+
+            // Gemini: the air zone, mutable multiples of 3, Mercury's root 5, lucky 11s
+            let weights = apply(&WesternAstrology::new(WesternRules::builtin()), &ctx);
+            let gemini: Vec<usize> = (1..=range_len)
+                .filter(|&i| {
+                    let pos = i as f64 / range_len as f64;
+                    (pos > 0.3 && pos <= 0.7)
+                        || i % 3 == 0
+                        || (i - 1) % 9 + 1 == 5
+                        || [11, 22, 33].contains(&i)
+                })
+                .collect();
+            assert_eq!(boosted(&weights), gemini, "Western on {}", max);
+
+            // A Horse in the neutral 甲辰 year: the upper half, plus Wood's 3 and 8
+            let weights = apply(&ChineseZodiacModule, &ctx);
+            let horse: Vec<usize> = (1..=range_len)
+                .filter(|&i| i * 2 > range_len || [3, 8].contains(&(i % 10)))
+                .collect();
+            assert_eq!(boosted(&weights), horse, "Chinese on {}", max);
+
+            // Day master 丁 (Fire), muted by the 辰巳天中殺 year but still favoring
+            // numbers whose digits add up to more than 5
+            let weights = apply(&SanmeiModule, &ctx);
+            let fire: Vec<usize> = (1..=range_len).filter(|&i| i / 10 + i % 10 > 5).collect();
+            assert_eq!(boosted(&weights), fire, "Sanmei on {}", max);
+        }
+    }
+
+    #[test]
+    fn rituals_boost_their_numbers() {
+        // SHA-256 echoes of observer 0xABCD that rise above the middle
+        const RESONANT: [usize; 23] = [
+            3, 4, 5, 7, 11, 12, 13, 14, 15, 17, 20, 22, 23, 24, 26, 27, 28, 31, 32, 39, 40, 41, 43,
+        ];
+        for (max, count) in GAMES {
+            let mut ctx = OracleContext::new(max, count, jst(2024, 2, 10), full_seeker());
+            let range_len = max as usize;
+            ctx.seed = 42;
+            ctx.observer_resonance = Some(0xABCD);
+            ctx.machine = MachineState {
+                cpu_load: Some(0.0),
+                uptime_secs: Some(3 * 86_400),
+                process_count: Some(7),
+                fingerprint: Some(0x0001_0002_0003_0000),
+                ..MachineState::default()
+            };
+
+            // Seed 42 draws The Tower (R), Ace of Pentacles, The Chariot (R): only the
+            // Ace lifts anything, the numbers ending in 1 in the lowest quarter
+            let weights = apply(&TarotModule, &ctx);
+            let ace: Vec<usize> = (1..=range_len)
+                .filter(|&i| i % 10 == 1 && (i - 1) * 4 / range_len == 0)
+                .collect();
+            assert_eq!(boosted(&weights), ace, "Tarot on {}", max);
+
+            // #14 大有 moving to #44 姤 (wrapped into the range); 離 3 and 乾 1 as last digits
+            let weights = apply(&IChingModule, &ctx);
+            let relating = (44 - 1) % range_len + 1;
+            let cast: Vec<usize> = (1..=range_len)
+                .filter(|&i| i == 14 || i == relating || [1, 3].contains(&(i % 10)))
+                .collect();
+            assert_eq!(boosted(&weights), cast, "I Ching on {}", max);
+
+            // Pulse at 1 (and 2 beside it), three days awake -> 4, root 7 crowd,
+            // guardians 2, 3 and 4 from the fingerprint
+            let weights = apply(&MachineSpiritModule, &ctx);
+            let spirit: Vec<usize> = (1..=range_len)
+                .filter(|&i| i <= 4 || (i - 1) % 9 + 1 == 7)
+                .collect();
+            assert_eq!(boosted(&weights), spirit, "MachineSpirit on {}", max);
+
+            let weights = apply(&ResonanceModule, &ctx);
+            let resonant: Vec<usize> = RESONANT.into_iter().filter(|&i| i <= range_len).collect();
+            assert_eq!(boosted(&weights), resonant, "Resonance on {}", max);
+        }
+    }
+
+    #[test]
+    fn rituals_follow_the_seed() {
+        let mut ctx = OracleContext::new(43, 6, jst(2024, 2, 10), full_seeker());
+        ctx.seed = 42;
+        let tarot = apply(&TarotModule, &ctx);
+        let iching = apply(&IChingModule, &ctx);
+        assert_eq!(tarot, apply(&TarotModule, &ctx));
+        assert_eq!(iching, apply(&IChingModule, &ctx));
+        ctx.seed = 43;
+        assert_ne!(
+            (tarot, iching),
+            (apply(&TarotModule, &ctx), apply(&IChingModule, &ctx))
+        );
+    }
+}
@@ -1,75 +1,202 @@
+use crate::calendar::{self, FiveElement};
+use crate::generator;
+use crate::iching::{Cast, Trigram};
+use crate::kyusei::{self, Direction, Mansion, Star};
 use crate::oracle::{
-    AuraColor, ChineseZodiac, DivinationModule, MoonPhase, OracleContext, Rokuyo, WesternZodiac,
+    AuraColor, ChineseZodiac, DivinationModule, Gender, OracleContext, Rokuyo, WesternZodiac,
+    ZodiacRelation,
 };
+use crate::oracle_sources::MachineState;
+use crate::tarot::{Arcana, Card, Suit};
 use chrono::Datelike;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
 
 // --- 1. Western Astrology ---
 
-pub struct WesternAstrology;
+/// Built-in rules; see the file for the format.
+const WESTERN_RULES: &str = include_str!("data/western_zodiac.toml");
+const WESTERN_RULES_FILE: &str = "western_zodiac.toml";
+
+#[derive(Debug, Deserialize)]
+struct ElementRule {
+    zone: [f64; 2],
+    boost: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModalityRule {
+    step: usize,
+    offset: usize,
+    boost: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignRule {
+    element: String,
+    modality: String,
+    ruler: String,
+    ruler_numbers: Vec<usize>,
+    lucky_numbers: Vec<usize>,
+    prime_boost: Option<f64>,
+    motto: String,
+}
+
+/// Weight rules for all twelve signs, loaded from TOML.
+#[derive(Debug, Deserialize)]
+pub struct WesternRules {
+    ruler_boost: f64,
+    lucky_boost: f64,
+    elements: HashMap<String, ElementRule>,
+    modalities: HashMap<String, ModalityRule>,
+    signs: HashMap<String, SignRule>,
+}
+
+fn sign_key(sign: WesternZodiac) -> String {
+    format!("{:?}", sign).to_lowercase()
+}
+
+/// 1–9, e.g. 38 -> 3 + 8 = 11 -> 2.
+fn digital_root(n: usize) -> usize {
+    if n == 0 {
+        0
+    } else {
+        1 + (n - 1) % 9
+    }
+}
+
+impl WesternRules {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let rules: WesternRules = toml::from_str(text).map_err(|e| e.to_string())?;
+        for sign in WesternZodiac::ALL {
+            let key = sign_key(sign);
+            let rule = rules
+                .signs
+                .get(&key)
+                .ok_or_else(|| format!("missing rules for sign '{}'", key))?;
+            if !rules.elements.contains_key(&rule.element) {
+                return Err(format!("{}: unknown element '{}'", key, rule.element));
+            }
+            match rules.modalities.get(&rule.modality) {
+                // An offset of `step` or more would never match any number
+                Some(m) if m.offset < m.step => {}
+                Some(m) => {
+                    return Err(format!(
+                        "{}: modality '{}' needs 0 <= offset < step (step {}, offset {})",
+                        key, rule.modality, m.step, m.offset
+                    ))
+                }
+                None => return Err(format!("{}: unknown modality '{}'", key, rule.modality)),
+            }
+        }
+        Ok(rules)
+    }
+
+    pub fn builtin() -> Self {
+        Self::parse(WESTERN_RULES).expect("built-in western zodiac rules are valid")
+    }
+
+    /// The user's rules file if present and valid, the built-in rules otherwise.
+    pub fn load() -> Self {
+        let Some(path) = crate::config::config_dir().map(|d| d.join(WESTERN_RULES_FILE)) else {
+            return Self::builtin();
+        };
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            return Self::builtin();
+        };
+        match Self::parse(&text) {
+            Ok(rules) => {
+                eprintln!("[Astrology] Reading the stars from {}", path.display());
+                rules
+            }
+            Err(e) => {
+                eprintln!(
+                    "[Astrology] Ignoring {} ({}); using built-in rules.",
+                    path.display(),
+                    e
+                );
+                Self::builtin()
+            }
+        }
+    }
+
+    fn sign(&self, sign: WesternZodiac) -> &SignRule {
+        // parse() guarantees every sign, element and modality is present
+        &self.signs[&sign_key(sign)]
+    }
+
+    /// Applies the sign's element, modality, ruler and lucky-number rules.
+    fn weigh(&self, sign: WesternZodiac, weights: &mut [f64]) {
+        let rule = self.sign(sign);
+        let element = &self.elements[&rule.element];
+        let modality = &self.modalities[&rule.modality];
+        let range_len = weights.len() - 1; // 1-based
+
+        for (i, w) in weights.iter_mut().enumerate().skip(1) {
+            let pos = i as f64 / range_len as f64;
+            if pos > element.zone[0] && pos <= element.zone[1] {
+                *w *= element.boost;
+            }
+            if i % modality.step == modality.offset {
+                *w *= modality.boost;
+            }
+            if rule.ruler_numbers.contains(&digital_root(i)) {
+                *w *= self.ruler_boost;
+            }
+            if rule.lucky_numbers.contains(&i) {
+                *w *= self.lucky_boost;
+            }
+            if let Some(boost) = rule.prime_boost {
+                if is_prime(i as u32) {
+                    *w *= boost;
+                }
+            }
+        }
+    }
+}
+
+pub struct WesternAstrology {
+    rules: WesternRules,
+}
+
+impl WesternAstrology {
+    pub fn new(rules: WesternRules) -> Self {
+        Self { rules }
+    }
+}
 
 impl DivinationModule for WesternAstrology {
     fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
         if let Some(sign) = ctx.western_zodiac {
+            let rule = self.rules.sign(sign);
             eprintln!("[Astrology] Sign: {:?} derived from birth date.", sign);
-            let range_len = weights.len() - 1; // 1-based
-
-            match sign {
-                WesternZodiac::Aries => {
-                    eprintln!("            Favoring bold prime numbers & high ranges.");
-                    for i in 1..=range_len {
-                        // Boost high range
-                        if i > (range_len * 7 / 10) {
-                            weights[i] *= 1.2;
-                        }
-                        if is_prime(i as u32) {
-                            weights[i] *= 1.3;
-                        }
-                    }
-                }
-                WesternZodiac::Taurus => {
-                    eprintln!(
-                        "            Favoring stability (numbers ending in 0, 5) and low range."
-                    );
-                    for i in 1..=range_len {
-                        if i <= (range_len / 2) {
-                            weights[i] *= 1.2;
-                        }
-                        if i % 5 == 0 {
-                            weights[i] *= 1.3;
-                        }
-                    }
-                }
-                WesternZodiac::Gemini => {
-                    eprintln!("            Favoring duality and communication (double digits).");
-                    for i in 1..=range_len {
-                        if i > 10 && i % 11 == 0 {
-                            weights[i] *= 1.5;
-                        }
-                    }
-                }
-                WesternZodiac::Cancer => {
-                    eprintln!("            Favoring numbers near the home (low range).");
-                    for i in 1..=range_len {
-                        if i <= (range_len / 3) {
-                            weights[i] *= 1.3;
-                        }
-                    }
-                }
-                // Skip others for brevity, can enable generic logic
-                _ => {
-                    eprintln!("            Generic blessings for {:?}.", sign);
-                }
-            }
+            eprintln!(
+                "            {} {} ruled by {}. {}",
+                rule.modality, rule.element, rule.ruler, rule.motto
+            );
+            self.rules.weigh(sign, weights);
         }
     }
 }
 
+/// Which of `parts` equal bands (0-based, from the bottom) number `i` falls in.
+/// Every number lands in exactly one band, including the tail of an uneven split.
+fn band(i: usize, range_len: usize, parts: usize) -> usize {
+    (i - 1) * parts / range_len
+}
+
+/// The eighth of the range a direction rules, clockwise from north at the bottom.
+fn in_direction(dir: Direction, i: usize, range_len: usize) -> bool {
+    band(i, range_len, 8) == dir.index()
+}
+
 fn is_prime(n: u32) -> bool {
     if n <= 1 {
         return false;
     }
     for i in 2..=((n as f64).sqrt() as u32) {
-        if n % i == 0 {
+        if n.is_multiple_of(i) {
             return false;
         }
     }
@@ -80,147 +207,268 @@ fn is_prime(n: u32) -> bool {
 
 pub struct ChineseZodiacModule;
 
+const FIBONACCI: [usize; 9] = [1, 2, 3, 5, 8, 13, 21, 34, 55];
+
+impl ChineseZodiacModule {
+    /// Boost for number `i` from the animal's own trait, if it applies.
+    fn animal_trait(zodiac: ChineseZodiac, i: usize, range_len: usize) -> Option<f64> {
+        let pos = i as f64 / range_len as f64;
+        let hit = |cond: bool, boost: f64| cond.then_some(boost);
+        match zodiac {
+            ChineseZodiac::Rat => hit(i <= 10, 1.4),
+            ChineseZodiac::Ox => hit(pos <= 0.5 && i.is_multiple_of(2), 1.25),
+            ChineseZodiac::Tiger => hit(!i.is_multiple_of(2), 1.2),
+            ChineseZodiac::Rabbit => hit(pos > 1.0 / 3.0 && pos <= 2.0 / 3.0, 1.2),
+            ChineseZodiac::Dragon => hit(i > range_len.saturating_sub(10), 1.5),
+            ChineseZodiac::Snake => hit(FIBONACCI.contains(&i), 1.35),
+            ChineseZodiac::Horse => hit(pos > 0.5, 1.2),
+            ChineseZodiac::Goat => hit(i.is_multiple_of(3), 1.2),
+            ChineseZodiac::Monkey => hit(i > 9 && i.is_multiple_of(11), 1.5),
+            ChineseZodiac::Rooster => hit(i.is_multiple_of(5), 1.25),
+            ChineseZodiac::Dog => hit(is_prime(i as u32), 1.2),
+            ChineseZodiac::Pig => hit(pos > 2.0 / 3.0, 1.25),
+        }
+    }
+
+    fn motto(zodiac: ChineseZodiac) -> &'static str {
+        match zodiac {
+            ChineseZodiac::Rat => "Clever starts; boosting low numbers.",
+            ChineseZodiac::Ox => "Steady toil; boosting low even numbers.",
+            ChineseZodiac::Tiger => "Aggressive power; boosting odds.",
+            ChineseZodiac::Rabbit => "Gentle balance; boosting the middle third.",
+            ChineseZodiac::Dragon => "Empowering wide spread & large numbers.",
+            ChineseZodiac::Snake => "Coiled wisdom; boosting Fibonacci numbers.",
+            ChineseZodiac::Horse => "Galloping ahead; boosting the upper half.",
+            ChineseZodiac::Goat => "Harmony in threes; boosting multiples of 3.",
+            ChineseZodiac::Monkey => "Mischief in mirrors; boosting repdigits.",
+            ChineseZodiac::Rooster => "Punctual crow; boosting multiples of 5.",
+            ChineseZodiac::Dog => "Loyal and indivisible; boosting primes.",
+            ChineseZodiac::Pig => "Abundant harvest; boosting the upper third.",
+        }
+    }
+}
+
+/// Numbers cycle through the twelve branches: 1 = 子, 2 = 丑, … 13 = 子 again.
+fn number_branch(i: usize) -> u8 {
+    ((i - 1) % 12) as u8
+}
+
 impl DivinationModule for ChineseZodiacModule {
     fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
+        let range_len = weights.len() - 1;
+        let year = ctx.year_kanshi;
+        let current = ChineseZodiac::from_branch(year.branch());
+
         if let Some(zodiac) = ctx.chinese_zodiac {
             eprintln!(
                 "[Zodiac(Animal)] Year of the {:?} -> applying traits.",
                 zodiac
             );
-            let range_len = weights.len() - 1;
-
-            match zodiac {
-                ChineseZodiac::Dragon => {
-                    eprintln!("               Empowering wide spread & large numbers.");
-                    for i in 1..=range_len {
-                        if i > range_len.saturating_sub(10) {
-                            weights[i] *= 1.5;
-                        }
-                    }
-                }
-                ChineseZodiac::Rat => {
-                    eprintln!("               Clever starts; boosting low numbers.");
-                    for i in 1..=10 {
-                        if i < weights.len() {
-                            weights[i] *= 1.4;
-                        }
-                    }
-                }
-                ChineseZodiac::Tiger => {
-                    eprintln!("               Aggressive power; boosting odds.");
-                    for i in 1..=range_len {
-                        if i % 2 != 0 {
-                            weights[i] *= 1.2;
-                        }
-                    }
-                }
-                _ => {
-                    eprintln!("               Standard fortune for this animal.");
+            eprintln!("               {}", Self::motto(zodiac));
+            for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                if let Some(boost) = Self::animal_trait(zodiac, i, range_len) {
+                    *w *= boost;
                 }
             }
+
+            let relation = zodiac.relation_to(current);
+            let birth = zodiac.branch();
+            let now = current.branch();
+            eprintln!(
+                "               Meets the {:?} year ({}): {:?}.",
+                current, year, relation
+            );
+            for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                let b = number_branch(i);
+                *w *= match relation {
+                    // 三合: the whole trine resonates
+                    ZodiacRelation::Trine if b % 4 == birth % 4 => 1.2,
+                    // 六合: both partners lend their numbers
+                    ZodiacRelation::Harmony if b == birth || b == now => 1.25,
+                    // 六冲: the ruling animal pushes back, the native digs in
+                    ZodiacRelation::Clash if b == now => 0.85,
+                    ZodiacRelation::Clash if b == birth => 1.1,
+                    // 本命年: a year to tread carefully
+                    ZodiacRelation::Same if b == birth => 0.9,
+                    _ => 1.0,
+                };
+            }
+        }
+
+        // The year's heavenly stem colours every draw, birth date or not
+        let element = year.stem_element();
+        let digits = element.hetu_digits();
+        eprintln!(
+            "[Zodiac(Animal)] {} year, {} element -> boosting numbers ending in {} or {}.",
+            year,
+            element.kanji(),
+            digits[0],
+            digits[1]
+        );
+        for (i, w) in weights.iter_mut().enumerate().skip(1) {
+            if digits.contains(&(i % 10)) {
+                *w *= 1.15;
+            }
         }
     }
 }
 
-// --- 3. Sanmei (Simplified) ---
+// --- 3. Sanmei ---
 
 pub struct SanmeiModule;
 
+impl SanmeiModule {
+    /// Whether the element favors number `i`.
+    fn favors(element: FiveElement, i: usize) -> bool {
+        match element {
+            FiveElement::Wood => i.is_multiple_of(3),
+            FiveElement::Fire => (i / 10 + i % 10) > 5,
+            FiveElement::Earth => true,
+            FiveElement::Metal => i.is_multiple_of(2),
+            FiveElement::Water => matches!(i % 10, 2 | 3 | 8),
+        }
+    }
+}
+
 impl DivinationModule for SanmeiModule {
     fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
-        // Derived from year if birth_date is present
-        if let Some(date) = ctx.birth_date {
-            let year = date.year();
-            let stem = year % 10;
-
-            let (element_name, boost_fn): (&str, fn(usize, &mut f64)) = match stem {
-                4 | 5 => ("Wood", |i, w| {
-                    if i % 3 == 0 {
-                        *w *= 1.2
-                    }
-                }),
-                6 | 7 => ("Fire", |i, w| {
-                    if (i / 10 + i % 10) > 5 {
-                        *w *= 1.2
-                    }
-                }),
-                8 | 9 => ("Earth", |_, w| *w *= 1.05),
-                0 | 1 => ("Metal", |i, w| {
-                    if i % 2 == 0 {
-                        *w *= 1.2
-                    }
-                }),
-                2 | 3 => ("Water", |i, w| {
-                    if i % 10 == 2 || i % 10 == 3 || i % 10 == 8 {
-                        *w *= 1.2
-                    }
-                }),
-                _ => ("Unknown", |_, _| {}),
-            };
-
+        // The day master (日干) is the person themself
+        if let Some(pillars) = ctx.pillars {
+            let element = pillars.day_master();
             eprintln!(
-                "[Sanmei] Element: {} (Stem {}) -> biased weights.",
-                element_name, stem
+                "[Sanmei] Pillars: {} {} {} -> day master {} ({:?}).",
+                pillars.year,
+                pillars.month,
+                pillars.day,
+                element.kanji(),
+                element
             );
 
-            for i in 1..weights.len() {
-                boost_fn(i, &mut weights[i]);
+            // 天中殺: in a void year the element's favor is only half-felt
+            let void = pillars.void_branches();
+            let in_void = void.contains(&ctx.year_kanshi.branch());
+            let strength = if in_void {
+                eprintln!(
+                    "         {}{}天中殺 year -> the element's favor is muted.",
+                    calendar::BRANCHES[void[0] as usize],
+                    calendar::BRANCHES[void[1] as usize]
+                );
+                0.5
+            } else {
+                1.0
+            };
+            // Earth favors everything, so it only gets a gentle lift
+            let boost = if element == FiveElement::Earth {
+                0.05
+            } else {
+                0.2
+            };
+
+            for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                if Self::favors(element, i) {
+                    *w *= 1.0 + boost * strength;
+                }
             }
         }
     }
 }
 
-// --- 4. Moon Phase ---
+// --- 4. Kyusei Kigaku ---
 
-pub struct MoonPhaseModule;
+pub struct KyuseiModule;
 
-impl DivinationModule for MoonPhaseModule {
+impl DivinationModule for KyuseiModule {
     fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
         let range_len = weights.len() - 1;
-        match ctx.moon_phase {
-            MoonPhase::New => {
-                eprintln!("[Moon] Phase: New -> favoring beginnings (low numbers).");
-                for i in 1..=range_len / 2 {
-                    weights[i] *= 1.2;
-                }
+        let today = ctx.kyusei;
+        eprintln!(
+            "[Kyusei] Year {} / Month {} / Day {}.",
+            today.year, today.month, today.day
+        );
+
+        // The day star rules numbers sharing its digital root
+        for (i, w) in weights.iter_mut().enumerate().skip(1) {
+            if (i - 1) % 9 + 1 == today.day.number() as usize {
+                *w *= 1.1;
             }
-            MoonPhase::Waxing => {
-                eprintln!("[Moon] Phase: Waxing -> favoring growth (ascending preference).");
-                for i in 1..=range_len {
-                    // Linear boost
-                    let factor = 1.0 + (i as f64 / range_len as f64) * 0.3;
-                    weights[i] *= factor;
+        }
+
+        if let Some(honmei) = ctx.honmei_star {
+            let lucky = today.day_board().lucky_directions(honmei);
+            let names: Vec<&str> = lucky.iter().map(|d| d.kanji()).collect();
+            eprintln!(
+                "         本命星 {} -> lucky directions today: {}.",
+                honmei,
+                if names.is_empty() {
+                    "none (stay home)".to_string()
+                } else {
+                    names.join(", ")
                 }
-            }
-            MoonPhase::Full => {
-                eprintln!("[Moon] Phase: Full -> favoring abundance (even spread, high numbers).");
-                for i in range_len / 2..=range_len {
-                    weights[i] *= 1.25;
+            );
+            for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                if (i - 1) % 9 + 1 == honmei.number() as usize {
+                    *w *= 1.25;
                 }
-            }
-            MoonPhase::Waning => {
-                eprintln!("[Moon] Phase: Waning -> favoring release (decending preference).");
-                for i in 1..=range_len {
-                    let factor = 1.3 - (i as f64 / range_len as f64) * 0.3;
-                    weights[i] *= factor;
+                if lucky.iter().any(|d| in_direction(*d, i, range_len)) {
+                    *w *= 1.15;
                 }
             }
         }
     }
 }
 
-// --- 5. Rokuyo ---
+// --- 5. Moon Phase ---
+
+pub struct MoonPhaseModule;
+
+impl DivinationModule for MoonPhaseModule {
+    fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
+        let range_len = weights.len() - 1;
+        let moon = ctx.moon;
+        eprintln!(
+            "[Moon] Phase: {:?} ({:.1}°, {:.0}% lit, age {:.1} days) -> {}.",
+            moon.phase,
+            moon.angle,
+            moon.illumination * 100.0,
+            moon.age_days(),
+            if moon.is_waxing() {
+                "favoring growth"
+            } else {
+                "favoring release"
+            }
+        );
+
+        // Light follows the disc: a dark moon favors beginnings (low numbers),
+        // a bright moon favors abundance (high numbers), smoothly in between.
+        // The waxing/waning motion tilts the curve further (sin > 0 while waxing).
+        let light = moon.illumination;
+        let motion = moon.angle.to_radians().sin();
+        for (i, w) in weights.iter_mut().enumerate().skip(1) {
+            let pos = i as f64 / range_len as f64;
+            let glow = 1.0 + 0.3 * (light * pos + (1.0 - light) * (1.0 - pos));
+            let tide = 1.0 + 0.1 * motion * (pos - 0.5);
+            *w *= glow * tide;
+        }
+    }
+}
+
+// --- 6. Rokuyo ---
 
 pub struct RokuyoModule;
 
 impl DivinationModule for RokuyoModule {
     fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
-        match ctx.rokuyo {
+        let Some(rokuyo) = ctx.rokuyo else {
+            eprintln!("[Rokuyo] Beyond the 旧暦 tables -> the six days rest.");
+            return;
+        };
+        let range_len = weights.len() - 1;
+        match rokuyo {
             Rokuyo::Taian => {
                 eprintln!("[Rokuyo] Taian (Great Peace) -> Even numbers gain a gentle blessing.");
-                for i in 1..weights.len() {
+                for (i, w) in weights.iter_mut().enumerate().skip(1) {
                     if i % 2 == 0 {
-                        weights[i] *= 1.15;
+                        *w *= 1.15;
                     }
                 }
             }
@@ -236,85 +484,189 @@ impl DivinationModule for RokuyoModule {
             Rokuyo::Senkatsu => {
                 eprintln!("[Rokuyo] Senkatsu (Win Early) -> Boosting first half.");
                 let mid = weights.len() / 2;
-                for i in 1..mid {
-                    weights[i] *= 1.2;
+                for w in &mut weights[1..mid] {
+                    *w *= 1.2;
                 }
             }
             Rokuyo::Senbu => {
                 eprintln!("[Rokuyo] Senbu (Lose Early, Win Late) -> Boosting second half.");
                 let mid = weights.len() / 2;
-                for i in mid..weights.len() {
-                    weights[i] *= 1.2;
+                for w in &mut weights[mid..] {
+                    *w *= 1.2;
+                }
+            }
+            Rokuyo::Tomobiki => {
+                eprintln!(
+                    "[Rokuyo] Tomobiki (Pull Friends) -> Morning and evening shine, noon rests."
+                );
+                // Both ends of the range are auspicious, the middle sixth is not
+                let sixth = (range_len / 6).max(1);
+                for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                    if i <= sixth || i > range_len - sixth {
+                        *w *= 1.2;
+                    } else if i.abs_diff(range_len.div_ceil(2)) < sixth / 2 + 1 {
+                        *w *= 0.9;
+                    }
                 }
             }
-            _ => {
-                eprintln!("[Rokuyo] {:?} -> General luck applied.", ctx.rokuyo);
+            Rokuyo::Shakku => {
+                eprintln!(
+                    "[Rokuyo] Shakku (Red Mouth) -> Only the hour of the horse (noon) is safe."
+                );
+                // 午の刻: luck gathers around the middle of the range, the rest is cautious
+                let center = range_len.div_ceil(2);
+                let reach = (range_len / 6).max(1);
+                for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                    if i.abs_diff(center) <= reach {
+                        *w *= 1.3;
+                    } else {
+                        *w *= 0.95;
+                    }
+                }
             }
         }
     }
 }
 
-// --- 6. Feng Shui / Aura ---
+// --- 7. Feng Shui / Aura ---
 
 pub struct FengShuiModule;
 
+impl FengShuiModule {
+    /// Each aura's element and trigram (後天八卦, which fixes its direction).
+    fn reading(aura: AuraColor) -> (FiveElement, Trigram, &'static str) {
+        match aura {
+            AuraColor::Red => (FiveElement::Fire, Trigram::Li, "Vitality"),
+            AuraColor::Blue => (FiveElement::Water, Trigram::Kan, "Flow"),
+            AuraColor::Green => (FiveElement::Wood, Trigram::Zhen, "Growth"),
+            AuraColor::Gold => (FiveElement::Metal, Trigram::Dui, "Wealth"),
+            AuraColor::Purple => (FiveElement::Wood, Trigram::Xun, "Prosperity"),
+            AuraColor::White => (FiveElement::Metal, Trigram::Qian, "Patronage"),
+            AuraColor::Black => (FiveElement::Earth, Trigram::Kun, "Grounding"),
+        }
+    }
+
+    /// Whether the element's zone holds `i`: Wood Q1, Water Q2, Fire Q3, Metal Q4;
+    /// Earth sits in the center (the two middle eighths).
+    fn in_element(element: FiveElement, i: usize, range_len: usize) -> bool {
+        match element {
+            FiveElement::Wood => band(i, range_len, 4) == 0,
+            FiveElement::Water => band(i, range_len, 4) == 1,
+            FiveElement::Fire => band(i, range_len, 4) == 2,
+            FiveElement::Metal => band(i, range_len, 4) == 3,
+            FiveElement::Earth => matches!(band(i, range_len, 8), 3 | 4),
+        }
+    }
+
+    fn mansion_boost(mansion: Mansion) -> f64 {
+        match mansion {
+            Mansion::ShengQi => 1.5,
+            Mansion::TianYi => 1.35,
+            Mansion::YanNian => 1.3,
+            Mansion::FuWei => 1.15,
+            Mansion::HuoHai => 0.9,
+            Mansion::WuGui => 0.85,
+            Mansion::LiuSha => 0.8,
+            Mansion::JueMing => 0.7,
+        }
+    }
+
+    /// 八宅: scales each direction's eighth by its mansion for the 命卦 — only the
+    /// faced direction, or all eight with `all_directions`.
+    fn weigh_mansions(
+        kua: Star,
+        facing: Option<Direction>,
+        all_directions: bool,
+        weights: &mut [f64],
+    ) {
+        let range_len = weights.len() - 1;
+        let dirs: Vec<Direction> = if all_directions {
+            Direction::ALL.to_vec()
+        } else {
+            facing.into_iter().collect()
+        };
+        for dir in dirs {
+            let boost = Self::mansion_boost(kyusei::mansion(kua, dir));
+            for i in (1..=range_len).filter(|&i| in_direction(dir, i, range_len)) {
+                weights[i] *= boost;
+            }
+        }
+    }
+
+    fn weigh(aura: AuraColor, weights: &mut [f64]) {
+        let range_len = weights.len() - 1;
+        let (element, trigram, _) = Self::reading(aura);
+        for (i, w) in weights.iter_mut().enumerate().skip(1) {
+            if Self::in_element(element, i, range_len) {
+                *w *= 1.3;
+            }
+            if in_direction(trigram.direction(), i, range_len) {
+                *w *= 1.15;
+            }
+        }
+    }
+}
+
 impl DivinationModule for FengShuiModule {
     fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
         if let Some(aura) = ctx.aura_color {
-            // map aura to element/direction logic
-            let range_len = weights.len() - 1;
-            let quadrant_size = range_len / 4;
-
-            // simple mapping
-            match aura {
-                AuraColor::Red => {
-                    // Fire / South
-                    eprintln!("[FengShui] Red Aura (South/Fire) -> Vitality in Q3.");
-                    // Boost Q3
-                    let start = quadrant_size * 2;
-                    let end = quadrant_size * 3;
-                    for i in start..end {
-                        if i < weights.len() {
-                            weights[i] *= 1.3;
-                        }
-                    }
-                }
-                AuraColor::Gold => {
-                    // Metal / West
-                    eprintln!("[FengShui] Gold Aura (West/Metal) -> Wealth in Q4.");
-                    let start = quadrant_size * 3;
-                    for i in start..weights.len() {
-                        weights[i] *= 1.3;
-                    }
-                }
-                AuraColor::Green => {
-                    // Wood / East
-                    eprintln!("[FengShui] Green Aura (East/Wood) -> Growth in Q1.");
-                    for i in 1..quadrant_size {
-                        weights[i] *= 1.3;
-                    }
-                }
-                AuraColor::Blue => {
-                    // Water / North
-                    eprintln!("[FengShui] Blue Aura (North/Water) -> Flow in Q2.");
-                    let start = quadrant_size;
-                    let end = quadrant_size * 2;
-                    for i in start..end {
-                        if i < weights.len() {
-                            weights[i] *= 1.3;
-                        }
-                    }
-                }
-                _ => {
-                    eprintln!("[FengShui] {:?} Aura -> Harmonizing all quadrants.", aura);
-                    // slight global boost or noise
+            let (element, trigram, motto) = Self::reading(aura);
+            eprintln!(
+                "[FengShui] {:?} Aura ({:?}/{:?}, {}) -> {} in the {} zone.",
+                aura,
+                trigram.direction(),
+                element,
+                trigram.kanji(),
+                motto,
+                element.kanji()
+            );
+            Self::weigh(aura, weights);
+        }
+
+        if ctx.facing.is_none() && !ctx.lucky_direction {
+            return;
+        }
+        let (Some(birth), Some(gender)) = (ctx.birth_date, ctx.gender) else {
+            eprintln!("[FengShui] 八宅 needs --birth-date and --gender; the compass stays still.");
+            return;
+        };
+        let kua = kyusei::kua(birth, gender == Gender::Female);
+        let group = if matches!(kua.number(), 1 | 3 | 4 | 9) {
+            "East"
+        } else {
+            "West"
+        };
+        eprintln!("[FengShui] 命卦 {} ({} group).", kua, group);
+        if let Some(dir) = ctx.facing {
+            let mansion = kyusei::mansion(kua, dir);
+            eprintln!(
+                "            Facing {:?} ({}) -> {} ({}).",
+                dir,
+                dir.kanji(),
+                mansion.kanji(),
+                if mansion.is_auspicious() {
+                    "吉"
+                } else {
+                    "凶"
                 }
-            }
+            );
+        }
+        if ctx.lucky_direction {
+            let best = Direction::ALL
+                .into_iter()
+                .find(|&d| kyusei::mansion(kua, d) == Mansion::ShengQi)
+                .unwrap_or(Direction::North);
+            eprintln!(
+                "            Lucky direction: {:?} ({}, 生気).",
+                best,
+                best.kanji()
+            );
         }
+        Self::weigh_mansions(kua, ctx.facing, ctx.lucky_direction, weights);
     }
 }
 
-// --- 7. Blood Type ---
+// --- 8. Blood Type ---
 
 pub struct BloodTypeModule;
 
@@ -327,35 +679,36 @@ impl DivinationModule for BloodTypeModule {
             match bt {
                 crate::oracle::BloodType::A => {
                     eprintln!("            Favoring balanced gaps and moderate sums.");
-                    // Boost middle 1/3
-                    let start = range_len / 3;
+                    // Boost middle 1/3 (never index 0, which is not a number)
+                    let start = (range_len / 3).max(1);
                     let end = range_len * 2 / 3;
-                    for i in start..=end {
-                        weights[i] *= 1.25;
+                    for w in &mut weights[start..=end] {
+                        *w *= 1.25;
                     }
                 }
                 crate::oracle::BloodType::B => {
                     eprintln!("            Favoring individuality (unusual numbers).");
                     // Boost primes? or ends
-                    for i in 1..=range_len {
-                        if i < 5 || i > range_len - 5 {
-                            weights[i] *= 1.3;
+                    for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                        // Written as a sum: `range_len - 5` underflows on small ranges
+                        if i < 5 || i + 5 > range_len {
+                            *w *= 1.3;
                         }
                     }
                 }
                 crate::oracle::BloodType::O => {
                     eprintln!("            Favoring broad ranges and big numbers.");
                     // Boost upper 50%
-                    for i in range_len / 2..=range_len {
-                        weights[i] *= 1.2;
+                    for w in &mut weights[(range_len / 2).max(1)..] {
+                        *w *= 1.2;
                     }
                 }
                 crate::oracle::BloodType::AB => {
                     eprintln!("            Favoring symmetrical patterns.");
                     // Boost numbers with double digits e.g. 11, 22, 33 OR sums
-                    for i in 1..=range_len {
+                    for (i, w) in weights.iter_mut().enumerate().skip(1) {
                         if i > 9 && i % 11 == 0 {
-                            weights[i] *= 1.5;
+                            *w *= 1.5;
                         }
                     }
                 }
@@ -364,16 +717,14 @@ impl DivinationModule for BloodTypeModule {
     }
 }
 
-// --- 8. Chaos / Entropy ---
+// --- 9. Chaos / Entropy ---
 
 pub struct ChaosModule;
 
 impl DivinationModule for ChaosModule {
-    fn apply(&self, _ctx: &OracleContext, weights: &mut [f64]) {
-        // Use memory address or time for chaos
-        let p = weights.as_ptr() as usize;
-        let t = chrono::Utc::now().timestamp_subsec_nanos();
-        let seed = (p as u64) ^ (t as u64);
+    fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
+        // The cracks follow the run's seed, so --seed replays them
+        let seed: u64 = generator::ritual_rng(ctx.seed, generator::CHAOS_STREAM).gen();
 
         eprintln!(
             "[Chaos] Tortoise shell cracks along unseen lines (entropy: 0x{:X}...).",
@@ -383,17 +734,17 @@ impl DivinationModule for ChaosModule {
         // Pseudo-random perturbation without changing rng state of main context
         // We use a simple hash to deterministicly noise it up based on 'seed' + index
 
-        for i in 1..weights.len() {
+        for (i, w) in weights.iter_mut().enumerate().skip(1) {
             let mut x = (seed ^ (i as u64)).wrapping_mul(0x517cc1b727220a95);
             x ^= x >> 12; // PCG-ish step
             let noise = (x % 100) as f64 / 1000.0; // 0.00 .. 0.09
 
-            weights[i] += noise;
+            *w += noise;
         }
     }
 }
 
-// --- 9. Stats / Hot-Cold (Mock) ---
+// --- 10. Stats / Hot-Cold ---
 
 pub struct StatsModule;
 
@@ -406,14 +757,658 @@ impl DivinationModule for StatsModule {
         // Let's say we favor numbers that match 'current day' or 'month' as hot numbers
         // and suppress numbers that match 'hour'
 
-        let day = ctx.now_utc.day();
-        let month = ctx.now_utc.month();
+        let day = ctx.now.day();
+        let month = ctx.now.month();
 
-        for i in 1..weights.len() {
+        for (i, w) in weights.iter_mut().enumerate().skip(1) {
             let n = i as u32;
             if n == day || n == month || n == (day + month) {
-                weights[i] *= 1.5; // HOT
+                *w *= 1.5; // HOT
+            }
+        }
+    }
+}
+
+// --- 11. Name (Seimei Handan) ---
+
+pub struct NameModule;
+
+impl DivinationModule for NameModule {
+    fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
+        if let Some(reading) = &ctx.name {
+            let range_len = weights.len() - 1;
+            let numbers = reading.numbers();
+            let summary: Vec<String> = numbers
+                .iter()
+                .map(|(label, n)| format!("{} {}", label, n))
+                .collect();
+            // An auspicious name speaks louder
+            let boost = if reading.is_auspicious() { 1.3 } else { 1.15 };
+            eprintln!(
+                "[Name] {} -> {} name.",
+                summary.join(" / "),
+                if reading.is_auspicious() {
+                    "Auspicious"
+                } else {
+                    "Modest"
+                }
+            );
+
+            for &(_, n) in &numbers {
+                // Counts beyond the range wrap around it
+                let target = (n as usize).saturating_sub(1) % range_len + 1;
+                weights[target] *= boost;
+                for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                    if digital_root(i) == digital_root(n as usize) {
+                        *w *= 1.05;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// --- 12. Tarot ---
+
+pub struct TarotModule;
+
+impl TarotModule {
+    fn draw(ctx: &OracleContext) -> Vec<Card> {
+        let mut rng = generator::ritual_rng(ctx.seed, generator::TAROT_STREAM);
+        ctx.tarot_spread.draw(&mut rng)
+    }
+
+    /// The range quarter a suit rules: Pentacles (earth) lowest, Wands (fire) highest.
+    fn suit_quarter(suit: Suit) -> usize {
+        match suit {
+            Suit::Pentacles => 0,
+            Suit::Cups => 1,
+            Suit::Swords => 2,
+            Suit::Wands => 3,
+        }
+    }
+
+    fn weigh(card: &Card, weights: &mut [f64]) {
+        let range_len = weights.len() - 1;
+        match card.arcana {
+            Arcana::Major(n) => {
+                // The Fool stands for 22, beyond The World; large counts wrap around
+                let target = if n == 0 { 22 } else { n as usize };
+                let target = (target - 1) % range_len + 1;
+                weights[target] *= if card.reversed { 0.85 } else { 1.3 };
+                if !card.reversed {
+                    for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                        if digital_root(i) == digital_root(target) {
+                            *w *= 1.05;
+                        }
+                    }
+                }
+            }
+            Arcana::Minor(suit, rank) => {
+                let quarter = Self::suit_quarter(suit);
+                for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                    if band(i, range_len, 4) == quarter && i % 10 == rank as usize % 10 {
+                        *w *= if card.reversed { 0.9 } else { 1.2 };
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl DivinationModule for TarotModule {
+    fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
+        let cards = Self::draw(ctx);
+        eprintln!("[Tarot] {:?} spread:", ctx.tarot_spread);
+        for (position, card) in ctx.tarot_spread.positions().iter().zip(&cards) {
+            eprintln!("        {:<16} {} — {}", position, card, card.meaning());
+            Self::weigh(card, weights);
+        }
+    }
+
+    fn columns(&self, ctx: &OracleContext) -> Vec<(&'static str, String)> {
+        let cards: Vec<String> = Self::draw(ctx).iter().map(|c| c.to_string()).collect();
+        vec![("tarot", cards.join(";"))]
+    }
+}
+
+// --- 13. I Ching ---
+
+pub struct IChingModule;
+
+impl IChingModule {
+    fn cast(ctx: &OracleContext) -> Cast {
+        Cast::throw(&mut generator::ritual_rng(
+            ctx.seed,
+            generator::ICHING_STREAM,
+        ))
+    }
+
+    fn weigh(cast: &Cast, weights: &mut [f64]) {
+        let range_len = weights.len() - 1;
+        let wrap = |n: usize| (n - 1) % range_len + 1;
+        let primary = cast.primary();
+        weights[wrap(primary.number() as usize)] *= 1.4;
+        if let Some(relating) = cast.relating() {
+            weights[wrap(relating.number() as usize)] *= 1.2;
+        }
+        // The trigrams' 先天 numbers echo in the last digit
+        let trigrams = [primary.lower.number(), primary.upper.number()];
+        for (i, w) in weights.iter_mut().enumerate().skip(1) {
+            if trigrams.contains(&((i % 10) as u8)) {
+                *w *= 1.1;
+            }
+        }
+    }
+}
+
+impl DivinationModule for IChingModule {
+    fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
+        let cast = Self::cast(ctx);
+        let primary = cast.primary();
+        eprintln!("[I Ching] Cast {} -> {}.", primary, {
+            let lines = cast.changing_lines();
+            match cast.relating() {
+                Some(relating) => format!("lines {:?} move toward {}", lines, relating),
+                None => "no moving lines".to_string(),
+            }
+        });
+        Self::weigh(&cast, weights);
+    }
+
+    fn columns(&self, ctx: &OracleContext) -> Vec<(&'static str, String)> {
+        let cast = Self::cast(ctx);
+        let mut value = cast.primary().number().to_string();
+        if let Some(relating) = cast.relating() {
+            value.push_str(&format!(">{}", relating.number()));
+        }
+        vec![("hexagram", value)]
+    }
+}
+
+// --- 14. Favorites ---
+
+pub struct FavoritesModule;
+
+impl DivinationModule for FavoritesModule {
+    fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
+        let favorites: Vec<u32> = ctx
+            .favorite_numbers
+            .iter()
+            .copied()
+            .filter(|&n| n >= 1 && (n as usize) < weights.len())
+            .collect();
+        if !favorites.is_empty() {
+            eprintln!("[Favorites] Cherished numbers {:?} -> glowing.", favorites);
+            for n in favorites {
+                weights[n as usize] *= 1.5;
+            }
+        }
+    }
+}
+
+// --- 15. Machine Spirit (Digital Animism) ---
+
+pub struct MachineSpiritModule;
+
+impl MachineSpiritModule {
+    /// Numbers the machine's fingerprint guards: three draws from its bytes.
+    fn guardians(fingerprint: u64, range_len: usize) -> Vec<usize> {
+        let bytes = fingerprint.to_be_bytes();
+        let mut guardians: Vec<usize> = bytes
+            .chunks(2)
+            .take(3)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]) as usize % range_len + 1)
+            .collect();
+        guardians.sort();
+        guardians.dedup();
+        guardians
+    }
+
+    fn weigh(machine: &MachineState, weights: &mut [f64]) {
+        let range_len = weights.len() - 1;
+
+        // Memory pressure: a strained spirit sinks to the low numbers, an idle one rises
+        if let Some(load) = machine.memory_load.filter(|l| l.is_finite()) {
+            let strain = (load as f64 / 100.0).clamp(0.0, 1.0);
+            for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                let pos = i as f64 / range_len as f64;
+                *w *= 1.0 + 0.2 * (0.5 - strain) * (2.0 * pos - 1.0);
+            }
+        }
+        // CPU load: the spirit's pulse beats at the same point of the range
+        if let Some(load) = machine.cpu_load.filter(|l| l.is_finite()) {
+            let pulse = ((load as f64 / 100.0).clamp(0.0, 1.0) * (range_len - 1) as f64).round()
+                as usize
+                + 1;
+            for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                *w *= match i.abs_diff(pulse) {
+                    0 => 1.3,
+                    1 => 1.1,
+                    _ => 1.0,
+                };
+            }
+        }
+        // Uptime: the spirit's age in days
+        if let Some(secs) = machine.uptime_secs {
+            let age = (secs / 86_400) as usize % range_len + 1;
+            weights[age] *= 1.2;
+        }
+        // Process count: the crowd within shares its digital root
+        if let Some(count) = machine.process_count {
+            for (i, w) in weights.iter_mut().enumerate().skip(1) {
+                if digital_root(i) == digital_root(count) {
+                    *w *= 1.1;
+                }
+            }
+        }
+        if let Some(fingerprint) = machine.fingerprint {
+            for n in Self::guardians(fingerprint, range_len) {
+                weights[n] *= 1.15;
+            }
+        }
+    }
+}
+
+impl DivinationModule for MachineSpiritModule {
+    fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
+        let machine = &ctx.machine;
+        if machine.is_silent() {
+            eprintln!("[Machine Spirit] The machine keeps its own counsel.");
+            return;
+        }
+        let reading = |v: Option<String>| v.unwrap_or_else(|| "?".to_string());
+        eprintln!(
+            "[Machine Spirit] Memory {}, CPU {}, awake {}, {} souls within.",
+            reading(machine.memory_load.map(|l| format!("{:.1}%", l))),
+            reading(machine.cpu_load.map(|l| format!("{:.1}%", l))),
+            reading(machine.uptime_secs.map(|s| format!(
+                "{}d {}h",
+                s / 86_400,
+                s % 86_400 / 3_600
+            ))),
+            reading(machine.process_count.map(|c| c.to_string())),
+        );
+        if let Some(fingerprint) = machine.fingerprint {
+            eprintln!(
+                "                 Guardian numbers {:?} (spirit {:016x}).",
+                Self::guardians(fingerprint, weights.len() - 1),
+                fingerprint
+            );
+        }
+        eprintln!("                 (replay with --machine-state {})", machine);
+        Self::weigh(machine, weights);
+    }
+
+    fn columns(&self, ctx: &OracleContext) -> Vec<(&'static str, String)> {
+        vec![("machine", ctx.machine.to_string())]
+    }
+}
+
+// --- 16. Observer Resonance ---
+
+pub struct ResonanceModule;
+
+impl ResonanceModule {
+    /// Each number's resonance in [0, 1): the first 8 bytes of
+    /// SHA-256(resonance as 16 big-endian bytes || number as 4 big-endian bytes).
+    fn scores(resonance: u128, range_len: usize) -> Vec<f64> {
+        use sha2::{Digest, Sha256};
+        (1..=range_len as u32)
+            .map(|n| {
+                let digest = Sha256::new()
+                    .chain_update(resonance.to_be_bytes())
+                    .chain_update(n.to_be_bytes())
+                    .finalize();
+                let x = u64::from_be_bytes(digest[..8].try_into().unwrap());
+                (x >> 11) as f64 / (1u64 << 53) as f64
+            })
+            .collect()
+    }
+
+    /// Scales every number between 0.85 and 1.15 by its resonance.
+    fn weigh(resonance: u128, weights: &mut [f64]) {
+        let range_len = weights.len() - 1;
+        for (i, score) in Self::scores(resonance, range_len).into_iter().enumerate() {
+            weights[i + 1] *= 0.85 + 0.3 * score;
+        }
+    }
+}
+
+impl DivinationModule for ResonanceModule {
+    fn apply(&self, ctx: &OracleContext, weights: &mut [f64]) {
+        let Some(resonance) = ctx.observer_resonance else {
+            eprintln!("[Resonance] No observer -> the field stays flat.");
+            return;
+        };
+        let scores = Self::scores(resonance, weights.len() - 1);
+        let mut order: Vec<usize> = (0..scores.len()).collect();
+        order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        let loudest: Vec<usize> = order.iter().take(3).map(|&i| i + 1).collect();
+        eprintln!(
+            "[Resonance] Observer {:x} -> strongest echoes at {:?}.",
+            resonance, loudest
+        );
+        Self::weigh(resonance, weights);
+    }
+
+    fn columns(&self, ctx: &OracleContext) -> Vec<(&'static str, String)> {
+        let value = ctx
+            .observer_resonance
+            .map(|r| format!("{:x}", r))
+            .unwrap_or_default();
+        vec![("resonance", value)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn western_weights(rules: &WesternRules, sign: WesternZodiac, max: usize) -> Vec<f64> {
+        let mut weights = vec![1.0; max + 1];
+        rules.weigh(sign, &mut weights);
+        weights
+    }
+
+    #[test]
+    fn every_sign_has_its_own_distribution() {
+        let rules = WesternRules::builtin();
+        for max in [43, 37, 31] {
+            let all: Vec<Vec<f64>> = WesternZodiac::ALL
+                .iter()
+                .map(|&s| western_weights(&rules, s, max))
+                .collect();
+            for (a, wa) in all.iter().enumerate() {
+                assert!(wa.iter().skip(1).any(|&w| w != 1.0));
+                for (b, wb) in all.iter().enumerate().skip(a + 1) {
+                    assert_ne!(
+                        wa,
+                        wb,
+                        "{:?} == {:?}",
+                        WesternZodiac::ALL[a],
+                        WesternZodiac::ALL[b]
+                    );
+                }
             }
         }
     }
+
+    #[test]
+    fn rules_follow_the_table() {
+        let rules = WesternRules::builtin();
+        // Aries: fire zone, cardinal (n % 4 == 1), Mars (root 9), lucky 1 and 9, primes
+        let w = western_weights(&rules, WesternZodiac::Aries, 43);
+        assert!((w[9] - 1.15 * 1.25 * 1.4).abs() < 1e-9);
+        assert!((w[41] - 1.2 * 1.15 * 1.3).abs() < 1e-9);
+        assert_eq!(w[20], 1.0);
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let broken = WESTERN_RULES.replace("element = \"water\"", "element = \"aether\"");
+        assert!(WesternRules::parse(&broken)
+            .unwrap_err()
+            .contains("unknown element"));
+        let missing = WESTERN_RULES.replace("[signs.pisces]", "[signs.ophiuchus]");
+        assert!(WesternRules::parse(&missing)
+            .unwrap_err()
+            .contains("pisces"));
+        for bad in ["step = 4\noffset = 4", "step = 0\noffset = 0"] {
+            let unreachable = WESTERN_RULES.replace("step = 4\noffset = 1", bad);
+            let err = WesternRules::parse(&unreachable).unwrap_err();
+            assert!(err.starts_with("aries: modality 'cardinal'"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn every_animal_has_its_own_trait() {
+        for max in [43, 37, 31] {
+            let all: Vec<Vec<Option<f64>>> = ChineseZodiac::ALL
+                .iter()
+                .map(|&z| {
+                    (1..=max)
+                        .map(|i| ChineseZodiacModule::animal_trait(z, i, max))
+                        .collect()
+                })
+                .collect();
+            for (a, ta) in all.iter().enumerate() {
+                assert!(
+                    ta.iter().any(Option::is_some),
+                    "{:?}",
+                    ChineseZodiac::ALL[a]
+                );
+                for tb in all.iter().skip(a + 1) {
+                    assert_ne!(ta, tb);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn numbers_cycle_through_branches() {
+        assert_eq!(number_branch(1), 0);
+        assert_eq!(number_branch(12), 11);
+        assert_eq!(number_branch(13), 0);
+    }
+
+    #[test]
+    fn sanmei_elements() {
+        assert!(SanmeiModule::favors(FiveElement::Wood, 9));
+        assert!(!SanmeiModule::favors(FiveElement::Metal, 9));
+        assert!(SanmeiModule::favors(FiveElement::Fire, 39));
+        assert!(!SanmeiModule::favors(FiveElement::Fire, 12));
+        assert!(SanmeiModule::favors(FiveElement::Water, 28));
+    }
+
+    #[test]
+    fn directions_split_the_range_evenly() {
+        for max in [43, 37, 31] {
+            let mut counts = [0; 8];
+            for i in 1..=max {
+                let owners: Vec<_> = Direction::ALL
+                    .iter()
+                    .filter(|d| in_direction(**d, i, max))
+                    .collect();
+                assert_eq!(owners.len(), 1);
+                counts[owners[0].index()] += 1;
+            }
+            assert!(counts.iter().all(|&c| c == max / 8 || c == max / 8 + 1));
+        }
+    }
+
+    const AURAS: [AuraColor; 7] = [
+        AuraColor::Red,
+        AuraColor::Blue,
+        AuraColor::Green,
+        AuraColor::Gold,
+        AuraColor::Purple,
+        AuraColor::White,
+        AuraColor::Black,
+    ];
+
+    #[test]
+    fn quadrants_cover_the_whole_range() {
+        for max in [43, 37] {
+            let elements = [
+                FiveElement::Wood,
+                FiveElement::Water,
+                FiveElement::Fire,
+                FiveElement::Metal,
+            ];
+            for i in 1..=max {
+                let owners = elements
+                    .iter()
+                    .filter(|&&e| FengShuiModule::in_element(e, i, max))
+                    .count();
+                assert_eq!(owners, 1, "{} of {}", i, max);
+            }
+            // The ends are no longer dropped
+            assert!(FengShuiModule::in_element(FiveElement::Wood, 1, max));
+            assert!(FengShuiModule::in_element(FiveElement::Metal, max, max));
+        }
+    }
+
+    #[test]
+    fn every_aura_shapes_the_weights() {
+        for max in [43, 37] {
+            let all: Vec<Vec<f64>> = AURAS
+                .iter()
+                .map(|&aura| {
+                    let mut weights = vec![1.0; max + 1];
+                    FengShuiModule::weigh(aura, &mut weights);
+                    weights
+                })
+                .collect();
+            for (a, wa) in all.iter().enumerate() {
+                assert!(wa[1..].iter().any(|&w| w > 1.0), "{:?}", AURAS[a]);
+                assert!(wa[1..].contains(&1.0), "{:?}", AURAS[a]);
+                for wb in &all[a + 1..] {
+                    assert_ne!(wa, wb);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn loto6_red_boosts_the_third_quadrant_and_the_south() {
+        let mut weights = vec![1.0; 44];
+        FengShuiModule::weigh(AuraColor::Red, &mut weights);
+        // Q3 of 43 is 23..=33; South (the fifth eighth) is 23..=27
+        assert_eq!(weights[22], 1.0);
+        assert!((weights[23] - 1.3 * 1.15).abs() < 1e-9);
+        assert!((weights[28] - 1.3).abs() < 1e-9);
+        assert!((weights[33] - 1.3).abs() < 1e-9);
+        assert_eq!(weights[34], 1.0);
+    }
+
+    #[test]
+    fn eight_mansions_follow_the_kua() {
+        // 命卦 1 (坎): 生気 SouthEast (17..=22 of 43), 延年 South (23..=27),
+        // 絶命 SouthWest (28..=32)
+        let kua = Star::new(1);
+        let mut weights = vec![1.0; 44];
+        FengShuiModule::weigh_mansions(kua, Some(Direction::South), false, &mut weights);
+        assert!((weights[23] - 1.3).abs() < 1e-9);
+        assert_eq!(weights[22], 1.0);
+        assert_eq!(weights[28], 1.0);
+
+        let mut weights = vec![1.0; 44];
+        FengShuiModule::weigh_mansions(kua, None, true, &mut weights);
+        assert!((weights[22] - 1.5).abs() < 1e-9);
+        assert!((weights[28] - 0.7).abs() < 1e-9);
+        assert!(weights[1..].iter().all(|&w| w != 1.0));
+    }
+
+    #[test]
+    fn machine_spirit_reads_every_metric() {
+        let mut weights = vec![1.0; 44];
+        let machine = MachineState {
+            memory_load: Some(50.0),
+            cpu_load: Some(50.0),
+            uptime_secs: Some(3 * 86_400 + 100),
+            process_count: Some(200),
+            fingerprint: None,
+        };
+        MachineSpiritModule::weigh(&machine, &mut weights);
+        // Half-full memory leaves the slope flat; pulse at 22, age 4, root of 200 is 2
+        assert!((weights[22] - 1.3).abs() < 1e-9);
+        assert!((weights[21] - 1.1).abs() < 1e-9);
+        assert!((weights[4] - 1.2).abs() < 1e-9);
+        assert!((weights[2] - 1.1).abs() < 1e-9);
+        assert_eq!(weights[5], 1.0);
+
+        // A strained machine tilts toward the low numbers
+        let mut weights = vec![1.0; 44];
+        let strained = MachineState {
+            memory_load: Some(90.0),
+            ..MachineState::default()
+        };
+        MachineSpiritModule::weigh(&strained, &mut weights);
+        assert!(weights[1] > 1.0 && weights[43] < 1.0);
+
+        // Readings that aren't numbers are ignored rather than poisoning every weight
+        let mut weights = vec![1.0; 44];
+        let broken = MachineState {
+            memory_load: Some(f32::NAN),
+            cpu_load: Some(f32::INFINITY),
+            ..MachineState::default()
+        };
+        MachineSpiritModule::weigh(&broken, &mut weights);
+        assert!(weights.iter().all(|&w| w == 1.0));
+    }
+
+    #[test]
+    fn machine_fingerprint_is_stable() {
+        use crate::oracle_sources::fingerprint;
+        let a = fingerprint("0123456789abcdef\n");
+        assert_eq!(a, fingerprint("0123456789abcdef"));
+        assert_ne!(a, fingerprint("fedcba9876543210"));
+        let guardians = MachineSpiritModule::guardians(a, 43);
+        assert!(!guardians.is_empty() && guardians.len() <= 3);
+        assert!(guardians.iter().all(|&n| (1..=43).contains(&n)));
+        assert_eq!(guardians, MachineSpiritModule::guardians(a, 43));
+    }
+
+    #[test]
+    fn resonance_is_reproducible() {
+        let weigh = |resonance| {
+            let mut weights = vec![1.0; 44];
+            ResonanceModule::weigh(resonance, &mut weights);
+            weights
+        };
+        let a = weigh(0x18dfa54d013efe30);
+        assert_eq!(a, weigh(0x18dfa54d013efe30));
+        assert_ne!(a, weigh(0x18dfa54d013efe31));
+        assert!(a[1..].iter().all(|w| (0.85..1.15).contains(w)));
+        // The scores spread over the whole band rather than clumping
+        assert!(a[1..].iter().any(|&w| w < 0.95) && a[1..].iter().any(|&w| w > 1.05));
+        // A number keeps its score whatever the size of the game
+        let scores = ResonanceModule::scores(7, 43);
+        assert_eq!(scores[..31], ResonanceModule::scores(7, 31)[..]);
+    }
+
+    #[test]
+    fn tarot_cards_move_their_numbers() {
+        let mut weights = vec![1.0; 44];
+        let star = Card {
+            arcana: Arcana::Major(17),
+            reversed: false,
+        };
+        TarotModule::weigh(&star, &mut weights);
+        assert!((weights[17] - 1.3 * 1.05).abs() < 1e-9);
+        assert!((weights[8] - 1.05).abs() < 1e-9);
+
+        // Three of Wands: the top quarter (34..=43) numbers ending in 3
+        let mut weights = vec![1.0; 44];
+        let wands = Card {
+            arcana: Arcana::Minor(Suit::Wands, 3),
+            reversed: true,
+        };
+        TarotModule::weigh(&wands, &mut weights);
+        assert_eq!(weights[43], 0.9);
+        assert_eq!(weights[13], 1.0);
+        assert_eq!(weights.iter().filter(|&&w| w != 1.0).count(), 1);
+    }
+
+    #[test]
+    fn hexagrams_wrap_into_the_range() {
+        // 未済 (#64) over Loto7's 37 numbers lands on 27; 坎 6 and 離 3 echo in last digits
+        let cast = Cast {
+            lines: [8, 7, 8, 7, 8, 7],
+        };
+        let mut weights = vec![1.0; 38];
+        IChingModule::weigh(&cast, &mut weights);
+        assert!((weights[27] - 1.4).abs() < 1e-9);
+        assert!((weights[16] - 1.1).abs() < 1e-9);
+        assert!((weights[33] - 1.1).abs() < 1e-9);
+        assert_eq!(weights[5], 1.0);
+    }
+
+    #[test]
+    fn digital_roots() {
+        assert_eq!(digital_root(9), 9);
+        assert_eq!(digital_root(38), 2);
+        assert_eq!(digital_root(43), 7);
+    }
 }
@@ -0,0 +1,265 @@
+//! Where the Oracle's observations come from: the clock, the observer's entropy and the
+//! machine's metrics. The defaults read the real world; the fixed sources replay a run.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+/// Tells the time of the divination.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock stopped at one instant.
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// The observer's contribution to the reading (the "resonance").
+pub trait Entropy {
+    fn resonance(&mut self) -> Option<u128>;
+}
+
+/// Times the observer's ENTER keypress and mixes it with the system time.
+pub struct KeypressEntropy;
+
+impl Entropy for KeypressEntropy {
+    fn resonance(&mut self) -> Option<u128> {
+        use std::io::{self, Write};
+        use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+        eprintln!("🌌 Awaiting Observer Intervention...");
+        eprint!("   Press [ENTER] when you feel the cosmic alignment: ");
+        io::stderr().flush().ok()?;
+
+        let mut input = String::new();
+        let start = Instant::now();
+        // A closed or unreadable stdin means nobody is there to observe
+        if io::stdin().read_line(&mut input).ok()? == 0 {
+            eprintln!();
+            return None;
+        }
+
+        let elapsed = start.elapsed().as_nanos();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos();
+        let resonance = elapsed ^ timestamp; // XOR mixing
+
+        eprintln!(
+            "⚡ Quantum state collapsed at {}ns. Resonance: {:x}",
+            elapsed, resonance
+        );
+        Some(resonance)
+    }
+}
+
+/// A resonance recorded earlier, or none at all.
+pub struct FixedEntropy(pub Option<u128>);
+
+impl Entropy for FixedEntropy {
+    fn resonance(&mut self) -> Option<u128> {
+        self.0
+    }
+}
+
+/// The machine's state as the Oracle sees it. Every reading may be missing.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MachineState {
+    /// Memory usage percentage (0.0 - 100.0).
+    pub memory_load: Option<f32>,
+    /// One-minute load average per core, as a percentage (0.0 - 100.0).
+    pub cpu_load: Option<f32>,
+    pub uptime_secs: Option<u64>,
+    pub process_count: Option<usize>,
+    /// SHA-256 of the machine id, cut to 64 bits; the id itself is never kept.
+    pub fingerprint: Option<u64>,
+}
+
+impl MachineState {
+    pub fn is_silent(&self) -> bool {
+        *self == MachineState::default()
+    }
+}
+
+/// A load percentage as the Oracle accepts it: finite and within 0–100.
+pub fn parse_load(s: &str) -> Result<f32, String> {
+    let load: f32 = s.trim().parse().map_err(|e| format!("{}: {}", s, e))?;
+    if !(0.0..=100.0).contains(&load) {
+        return Err(format!("負荷は 0〜100 (%) で指定してください: {}", s));
+    }
+    Ok(load)
+}
+
+/// The record printed after a reading, e.g. `mem=42.5;cpu=12;up=3600;procs=250;id=…`;
+/// missing readings are left out, and a silent machine is `none`.
+impl fmt::Display for MachineState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields = Vec::new();
+        if let Some(load) = self.memory_load {
+            fields.push(format!("mem={}", load));
+        }
+        if let Some(load) = self.cpu_load {
+            fields.push(format!("cpu={}", load));
+        }
+        if let Some(secs) = self.uptime_secs {
+            fields.push(format!("up={}", secs));
+        }
+        if let Some(count) = self.process_count {
+            fields.push(format!("procs={}", count));
+        }
+        if let Some(fingerprint) = self.fingerprint {
+            fields.push(format!("id={:016x}", fingerprint));
+        }
+        if fields.is_empty() {
+            return f.write_str("none");
+        }
+        f.write_str(&fields.join(";"))
+    }
+}
+
+impl FromStr for MachineState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut state = MachineState::default();
+        let s = s.trim();
+        if s == "none" {
+            return Ok(state);
+        }
+        for field in s.split(';') {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| format!("キー=値 の形ではありません: {}", field))?;
+            let value = value.trim();
+            let invalid = |e: &dyn fmt::Display| format!("{}: {}", field, e);
+            match key.trim() {
+                "mem" => state.memory_load = Some(parse_load(value)?),
+                "cpu" => state.cpu_load = Some(parse_load(value)?),
+                "up" => state.uptime_secs = Some(value.parse().map_err(|e| invalid(&e))?),
+                "procs" => state.process_count = Some(value.parse().map_err(|e| invalid(&e))?),
+                "id" => {
+                    state.fingerprint =
+                        Some(u64::from_str_radix(value, 16).map_err(|e| invalid(&e))?)
+                }
+                other => return Err(format!("不明な項目です: {}", other)),
+            }
+        }
+        Ok(state)
+    }
+}
+
+/// Stable 64-bit fingerprint of a machine id.
+pub fn fingerprint(machine_id: &str) -> u64 {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(format!(
+        "loto_random_cli/machine-spirit/{}",
+        machine_id.trim()
+    ));
+    u64::from_be_bytes(digest[..8].try_into().unwrap())
+}
+
+pub trait SystemMetrics {
+    fn read(&mut self) -> MachineState;
+}
+
+/// Reads the host through `sysinfo`; the machine id comes from `/etc/machine-id`
+/// where there is one, the host name otherwise.
+pub struct HostMetrics;
+
+impl SystemMetrics for HostMetrics {
+    fn read(&mut self) -> MachineState {
+        use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+        let mut sys = System::new();
+        sys.refresh_memory();
+        let total_mem = sys.total_memory();
+        let used_mem = sys.used_memory();
+        let memory_load = (total_mem > 0).then(|| (used_mem as f32 / total_mem as f32) * 100.0);
+
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let load = System::load_average().one;
+        let cpu_load = (load > 0.0).then(|| (load as f32 / cores as f32 * 100.0).min(100.0));
+
+        let uptime = System::uptime();
+        let processes = sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+
+        let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+            .iter()
+            .find_map(|path| std::fs::read_to_string(path).ok())
+            .filter(|id| !id.trim().is_empty())
+            .or_else(System::host_name);
+
+        MachineState {
+            memory_load,
+            cpu_load,
+            uptime_secs: (uptime > 0).then_some(uptime),
+            process_count: (processes > 0).then_some(processes),
+            fingerprint: machine_id.as_deref().map(fingerprint),
+        }
+    }
+}
+
+/// Metrics recorded earlier, or none at all (the machine spirit stays silent).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedMetrics(pub MachineState);
+
+impl SystemMetrics for FixedMetrics {
+    fn read(&mut self) -> MachineState {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn machine_state_round_trips_through_its_record() {
+        let state = MachineState {
+            memory_load: Some(42.37),
+            cpu_load: Some(0.0),
+            uptime_secs: Some(3 * 86_400 + 17),
+            process_count: Some(251),
+            fingerprint: Some(fingerprint("0123456789abcdef")),
+        };
+        let record = state.to_string();
+        assert_eq!(record.parse::<MachineState>(), Ok(state));
+
+        let partial = MachineState {
+            memory_load: Some(40.0),
+            ..MachineState::default()
+        };
+        assert_eq!(partial.to_string(), "mem=40");
+        assert_eq!("mem=40".parse(), Ok(partial));
+        assert_eq!(MachineState::default().to_string(), "none");
+        assert_eq!("none".parse(), Ok(MachineState::default()));
+
+        for bad in [
+            "mem=nan", "cpu=101", "mem=-1", "up=x", "id=zz", "temp=3", "mem",
+        ] {
+            assert!(bad.parse::<MachineState>().is_err(), "{}", bad);
+        }
+        assert!(parse_load("inf").is_err());
+        assert_eq!(parse_load("100"), Ok(100.0));
+    }
+}
@@ -0,0 +1,727 @@
+//! Office lottery pools (共同購入) stored in `pools.toml` under the config directory:
+//! who paid in, the tickets bought with the money, and how the winnings are split.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::beacon::Drawing;
+use crate::commit::TicketSpec;
+use crate::game::{GameType, TargetDraw};
+use crate::generator::Algorithm;
+
+/// One pool: its members' contributions and, once bought, its tickets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pool {
+    pub game: GameType,
+    /// Contribution of each member towards the next purchase, in yen
+    #[serde(default)]
+    pub members: BTreeMap<String, u64>,
+    /// The draw the current tickets are for
+    pub draw: Option<PoolDraw>,
+    /// Earlier draws, settled, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<PoolDraw>,
+}
+
+/// The tickets bought for one draw.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolDraw {
+    pub date: NaiveDate,
+    /// Estimated draw number (回号)
+    pub number: Option<u32>,
+    pub seed: u64,
+    /// The contributions spent on these tickets; the winnings are split by these
+    pub members: BTreeMap<String, u64>,
+    pub tickets: Vec<Vec<u32>>,
+    pub result: Option<PoolResult>,
+}
+
+impl PoolDraw {
+    /// The settlement of the recorded result, if any.
+    pub fn settlement(&self, game: GameType) -> Option<Settlement> {
+        let result = self.result.as_ref()?;
+        let wins: Vec<Win> = self
+            .tickets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, ticket)| {
+                let main = ticket
+                    .iter()
+                    .filter(|n| result.winning.main.contains(n))
+                    .count();
+                let bonus = ticket
+                    .iter()
+                    .filter(|n| result.winning.bonus.contains(n))
+                    .count();
+                let tier = game.prize_tier(main, bonus)?;
+                Some(Win {
+                    ticket: i + 1,
+                    tier,
+                    prize: result.prizes.get(tier as usize - 1).copied().unwrap_or(0),
+                })
+            })
+            .collect();
+        let winnings = wins.iter().map(|w| w.prize).sum();
+        let contributions: Vec<u64> = self.members.values().copied().collect();
+        let shares = self
+            .members
+            .iter()
+            .zip(split(winnings, &contributions))
+            .map(|((member, &contribution), payout)| Share {
+                member: member.clone(),
+                contribution,
+                payout,
+            })
+            .collect();
+        Some(Settlement {
+            date: self.date,
+            number: self.number,
+            wins,
+            winnings,
+            shares,
+        })
+    }
+}
+
+/// The winning numbers as entered after the draw, with the prize per ticket of each tier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolResult {
+    pub winning: Drawing,
+    /// Yen per winning ticket, 1等 first
+    pub prizes: Vec<u64>,
+}
+
+/// A winning ticket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Win {
+    /// 1-based ticket number
+    pub ticket: usize,
+    pub tier: u8,
+    pub prize: u64,
+}
+
+/// A member's part of the winnings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    pub member: String,
+    pub contribution: u64,
+    pub payout: u64,
+}
+
+/// Who won what, and who gets what.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settlement {
+    pub date: NaiveDate,
+    pub number: Option<u32>,
+    pub wins: Vec<Win>,
+    pub winnings: u64,
+    pub shares: Vec<Share>,
+}
+
+impl Settlement {
+    /// The settlement CSV: `member,contribution,share_percent,payout,target_draw,draw_date`,
+    /// the draw columns named as in the ticket CSV.
+    pub fn to_csv(&self) -> String {
+        let total: u64 = self.shares.iter().map(|s| s.contribution).sum();
+        let number = self.number.map(|n| n.to_string()).unwrap_or_default();
+        let mut csv =
+            String::from("member,contribution,share_percent,payout,target_draw,draw_date\n");
+        for share in &self.shares {
+            csv.push_str(&format!(
+                "{},{},{:.2},{},{},{}\n",
+                share.member,
+                share.contribution,
+                share.contribution as f64 * 100.0 / total.max(1) as f64,
+                share.payout,
+                number,
+                self.date
+            ));
+        }
+        csv
+    }
+}
+
+/// Splits `amount` yen in proportion to `weights`, rounding down and handing the
+/// leftover yen to the largest remainders (earlier entries first on ties), so the
+/// parts always add up to `amount`.
+pub fn split(amount: u64, weights: &[u64]) -> Vec<u64> {
+    let total: u128 = weights.iter().map(|&w| w as u128).sum();
+    if total == 0 {
+        return vec![0; weights.len()];
+    }
+    let exact: Vec<(u64, u128)> = weights
+        .iter()
+        .map(|&w| {
+            let scaled = amount as u128 * w as u128;
+            ((scaled / total) as u64, scaled % total)
+        })
+        .collect();
+    let mut parts: Vec<u64> = exact.iter().map(|&(part, _)| part).collect();
+    let left = amount - parts.iter().sum::<u64>();
+    let mut order: Vec<usize> = (0..parts.len()).collect();
+    order.sort_by(|&a, &b| exact[b].1.cmp(&exact[a].1));
+    for &i in order.iter().take(left as usize) {
+        parts[i] += 1;
+    }
+    parts
+}
+
+/// Member names end up in CSV rows, so keep them free of separators.
+fn validate_member(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.contains([',', '"', '\n', '\r']) {
+        return Err(format!(
+            "メンバー名は空にせず、カンマや引用符を含めないでください: {:?}",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// The winning numbers must be a valid draw of `game`.
+pub fn validate_drawing(game: GameType, drawing: &Drawing) -> Result<(), String> {
+    let (range, picks) = game.config();
+    if drawing.main.len() != picks || drawing.bonus.len() != game.bonus_count() {
+        return Err(format!(
+            "{}の当選数字は本数字{}個・ボーナス数字{}個です",
+            game.label(),
+            picks,
+            game.bonus_count()
+        ));
+    }
+    let all: Vec<u32> = drawing.main.iter().chain(&drawing.bonus).copied().collect();
+    for (i, n) in all.iter().enumerate() {
+        if !range.contains(n) {
+            return Err(format!(
+                "当選数字は {}〜{} で指定してください: {}",
+                range.start(),
+                range.end(),
+                n
+            ));
+        }
+        if all[..i].contains(n) {
+            return Err(format!("当選数字が重複しています: {}", n));
+        }
+    }
+    Ok(())
+}
+
+impl Pool {
+    pub fn new(game: GameType) -> Self {
+        Pool {
+            game,
+            members: BTreeMap::new(),
+            draw: None,
+            history: Vec::new(),
+        }
+    }
+
+    /// Total contributions waiting for the next purchase, in yen.
+    pub fn budget(&self) -> u64 {
+        self.members.values().sum()
+    }
+
+    /// How many tickets the budget buys.
+    pub fn ticket_count(&self) -> usize {
+        (self.budget() / self.game.ticket_price()) as usize
+    }
+
+    /// Adds `yen` to a member's contribution (joining them if new); returns the new total.
+    pub fn contribute(&mut self, member: &str, yen: u64) -> Result<u64, String> {
+        validate_member(member)?;
+        if yen == 0 {
+            return Err("出資額は1円以上で指定してください".to_string());
+        }
+        let total = self.members.entry(member.trim().to_string()).or_default();
+        *total += yen;
+        Ok(*total)
+    }
+
+    /// Takes `yen` off a member's contribution, or all of it when `None` (a member
+    /// left with nothing leaves the pool); returns what is left.
+    pub fn withdraw(&mut self, member: &str, yen: Option<u64>) -> Result<u64, String> {
+        let name = member.trim();
+        let pending = self.draw.as_ref().is_some_and(|d| d.result.is_none());
+        let total = self.members.get_mut(name).ok_or_else(|| {
+            if pending {
+                format!(
+                    "{} の次回分の出資はありません（購入済みのチケットの出資は `pool cancel` で戻せます）",
+                    name
+                )
+            } else {
+                format!("出資していないメンバーです: {}", name)
+            }
+        })?;
+        let yen = yen.unwrap_or(*total);
+        if yen == 0 || yen > *total {
+            return Err(format!("{} の出資は {}円 です: {}円", name, total, yen));
+        }
+        *total -= yen;
+        let left = *total;
+        if left == 0 {
+            self.members.remove(name);
+        }
+        Ok(left)
+    }
+
+    /// Cancels the tickets still awaiting their draw, giving their contributions
+    /// back to the pool.
+    pub fn cancel(&mut self) -> Result<PoolDraw, String> {
+        let draw = match self.draw.take() {
+            Some(draw) if draw.result.is_none() => draw,
+            settled => {
+                self.draw = settled;
+                return Err(
+                    "取り消せる購入がありません（精算済みの抽選は取り消せません）".to_string(),
+                );
+            }
+        };
+        for (member, yen) in &draw.members {
+            *self.members.entry(member.clone()).or_default() += yen;
+        }
+        Ok(draw)
+    }
+
+    /// The contributions a purchase spends: those waiting for it plus, as `replace`
+    /// gives them back, those of the tickets still awaiting their draw.
+    fn funds(&self) -> BTreeMap<String, u64> {
+        let mut funds = self.members.clone();
+        if let Some(pending) = self.draw.as_ref().filter(|d| d.result.is_none()) {
+            for (member, yen) in &pending.members {
+                *funds.entry(member.clone()).or_default() += yen;
+            }
+        }
+        funds
+    }
+
+    /// Buys as many tickets as the contributions allow for the draw on `date`, which
+    /// spends them. A settled draw moves to the history; `replace` gives back the
+    /// contributions of tickets still awaiting their draw and buys again.
+    pub fn buy(&mut self, date: NaiveDate, seed: u64, replace: bool) -> Result<&PoolDraw, String> {
+        if !self.game.is_draw_day(date) {
+            return Err(format!(
+                "{} は{}の抽選日ではありません",
+                date,
+                self.game.label()
+            ));
+        }
+        match &self.draw {
+            Some(d) if d.result.is_some() && replace => {
+                return Err(
+                    "精算済みの抽選は買い直せません（--replace なしで次の抽選を購入できます）"
+                        .to_string(),
+                )
+            }
+            Some(d) if d.result.is_none() && !replace => {
+                return Err("すでにチケットを購入済みです（買い直すなら --replace）".to_string())
+            }
+            _ => {}
+        }
+        let funds = self.funds();
+        let budget: u64 = funds.values().sum();
+        let count = (budget / self.game.ticket_price()) as usize;
+        if count == 0 {
+            return Err(format!(
+                "出資総額 {}円 では1口 ({}円) も買えません",
+                budget,
+                self.game.ticket_price()
+            ));
+        }
+        let spec = TicketSpec {
+            game: self.game,
+            algo: Algorithm::Pure,
+            count,
+            seed,
+        };
+        let target = TargetDraw::new(self.game, date);
+        let tickets = spec.tickets().map_err(|e| e.to_string())?;
+        if self.draw.as_ref().is_some_and(|d| d.result.is_some()) {
+            self.history.extend(self.draw.take());
+        }
+        self.members.clear();
+        Ok(self.draw.insert(PoolDraw {
+            date: target.date,
+            number: target.number,
+            seed,
+            members: funds,
+            tickets,
+            result: None,
+        }))
+    }
+
+    /// `base` with the prize of some tiers replaced.
+    fn prize_table(
+        &self,
+        mut base: Vec<u64>,
+        prizes: &BTreeMap<u8, u64>,
+    ) -> Result<Vec<u64>, String> {
+        for (&tier, &yen) in prizes {
+            let slot = base
+                .get_mut((tier as usize).wrapping_sub(1))
+                .ok_or_else(|| {
+                    format!(
+                        "{}の等級は 1〜{} です: {}",
+                        self.game.label(),
+                        self.game.reference_prizes().len(),
+                        tier
+                    )
+                })?;
+            *slot = yen;
+        }
+        Ok(base)
+    }
+
+    /// Records the winning numbers; `prizes` overrides the reference prize of some tiers.
+    pub fn enter_result(
+        &mut self,
+        winning: Drawing,
+        prizes: &BTreeMap<u8, u64>,
+    ) -> Result<Settlement, String> {
+        validate_drawing(self.game, &winning)?;
+        let table = self.prize_table(self.game.reference_prizes().to_vec(), prizes)?;
+        let draw = self
+            .draw
+            .as_mut()
+            .ok_or("まだチケットを購入していません (`pool tickets` で購入)")?;
+        draw.result = Some(PoolResult {
+            winning,
+            prizes: table,
+        });
+        Ok(self.settlement().expect("the result was just recorded"))
+    }
+
+    /// Replaces the prize of some tiers in the recorded result, once the actual
+    /// amounts are announced.
+    pub fn update_prizes(&mut self, prizes: &BTreeMap<u8, u64>) -> Result<Settlement, String> {
+        let recorded = self
+            .draw
+            .as_ref()
+            .and_then(|d| d.result.as_ref())
+            .ok_or("当選結果がまだありません (--numbers と --bonus で入力してください)")?;
+        let table = self.prize_table(recorded.prizes.clone(), prizes)?;
+        if let Some(result) = self.draw.as_mut().and_then(|d| d.result.as_mut()) {
+            result.prizes = table;
+        }
+        Ok(self.settlement().expect("the result is recorded"))
+    }
+
+    /// The settlement of the current draw's result, if any.
+    pub fn settlement(&self) -> Option<Settlement> {
+        self.draw.as_ref()?.settlement(self.game)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for name in self.members.keys() {
+            validate_member(name)?;
+        }
+        for draw in self.history.iter().chain(&self.draw) {
+            for name in draw.members.keys() {
+                validate_member(name)?;
+            }
+            if let Some(result) = &draw.result {
+                validate_drawing(self.game, &result.winning)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The whole pool file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pools {
+    #[serde(default)]
+    pub pools: BTreeMap<String, Pool>,
+}
+
+fn path() -> Result<PathBuf, String> {
+    crate::config::config_dir()
+        .map(|dir| dir.join("pools.toml"))
+        .ok_or_else(|| "設定ディレクトリが見つかりません (HOME 未設定)".to_string())
+}
+
+/// Pool names double as TOML keys and CLI values, so keep them plain.
+fn validate_key(key: &str) -> Result<(), String> {
+    let plain = key
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if key.is_empty() || !plain {
+        return Err(format!(
+            "プール名は英数字・-・_ で指定してください: {}",
+            key
+        ));
+    }
+    Ok(())
+}
+
+impl Pools {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let pools: Pools = toml::from_str(text).map_err(|e| e.to_string())?;
+        for (key, pool) in &pools.pools {
+            validate_key(key)?;
+            pool.validate().map_err(|e| format!("[{}] {}", key, e))?;
+        }
+        Ok(pools)
+    }
+
+    /// Reads the pool file; a missing file is no pools at all.
+    pub fn load() -> Result<Self, String> {
+        let path = path()?;
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn save(&self) -> Result<PathBuf, String> {
+        let path = path()?;
+        // Not pretty: that would spread every ticket over a line per number
+        let text = toml::to_string(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::write(&path, text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    pub fn create(&mut self, key: &str, game: GameType) -> Result<&mut Pool, String> {
+        validate_key(key)?;
+        if self.pools.contains_key(key) {
+            return Err(format!("プールはすでにあります: {}", key));
+        }
+        Ok(self.pools.entry(key.to_string()).or_insert(Pool::new(game)))
+    }
+
+    pub fn get(&self, key: &str) -> Result<&Pool, String> {
+        self.pools.get(key).ok_or_else(|| not_found(key))
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Result<&mut Pool, String> {
+        self.pools.get_mut(key).ok_or_else(|| not_found(key))
+    }
+
+    pub fn remove(&mut self, key: &str) -> Result<Pool, String> {
+        self.pools.remove(key).ok_or_else(|| not_found(key))
+    }
+}
+
+fn not_found(key: &str) -> String {
+    format!(
+        "プールが見つかりません: {} (`pool show` で一覧を確認できます)",
+        key
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn office() -> Pool {
+        let mut pool = Pool::new(GameType::Loto6);
+        pool.contribute("alice", 1000).unwrap();
+        pool.contribute("bob", 600).unwrap();
+        pool.contribute("carol", 300).unwrap();
+        pool.contribute("bob", 100).unwrap();
+        pool
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 2, 15).unwrap()
+    }
+
+    #[test]
+    fn split_keeps_every_yen() {
+        assert_eq!(split(1000, &[1, 1, 1]), vec![334, 333, 333]);
+        assert_eq!(split(1000, &[1, 2]), vec![333, 667]);
+        assert_eq!(split(0, &[5, 5]), vec![0, 0]);
+        assert_eq!(split(100, &[0, 0]), vec![0, 0]);
+        let parts = split(200_000_000, &[1000, 700, 300]);
+        assert_eq!(parts, vec![100_000_000, 70_000_000, 30_000_000]);
+        for amount in [1, 7, 999, 6_800, 10_001] {
+            let weights = [1000, 700, 300, 1];
+            assert_eq!(split(amount, &weights).iter().sum::<u64>(), amount);
+        }
+    }
+
+    #[test]
+    fn budget_buys_whole_tickets() {
+        let mut pool = office();
+        assert_eq!(pool.members["bob"], 700);
+        assert_eq!(pool.budget(), 2000);
+        assert_eq!(pool.ticket_count(), 10);
+        assert!(pool.contribute("dave", 0).is_err());
+        assert!(pool.contribute("a,b", 100).is_err());
+        assert!(pool.contribute(" ", 100).is_err());
+
+        let contributed = pool.members.clone();
+        assert!(pool.buy(date().pred_opt().unwrap(), 42, false).is_err());
+        let draw = pool.buy(date(), 42, false).unwrap().clone();
+        assert_eq!(draw.tickets.len(), 10);
+        assert!(draw.number.is_some());
+        assert_eq!(draw.members, contributed);
+        assert_eq!(pool.budget(), 0);
+
+        // New money waits for the next purchase, unless the tickets are bought again
+        pool.contribute("dave", 200).unwrap();
+        assert!(pool.buy(date(), 43, false).is_err());
+        let replaced = pool.buy(date(), 43, true).unwrap();
+        assert_eq!(replaced.tickets.len(), 11);
+        assert_eq!(replaced.members["dave"], 200);
+        assert_eq!(pool.budget(), 0);
+
+        let mut loto7 = Pool::new(GameType::Loto7);
+        loto7.contribute("alice", 299).unwrap();
+        let friday = date().succ_opt().unwrap();
+        assert!(loto7.buy(friday, 1, false).is_err());
+    }
+
+    #[test]
+    fn contributions_can_be_taken_back() {
+        let mut pool = office();
+        assert_eq!(pool.withdraw("bob", Some(300)), Ok(400));
+        assert!(pool.withdraw("bob", Some(401)).is_err());
+        assert!(pool.withdraw("bob", Some(0)).is_err());
+        assert_eq!(pool.withdraw(" carol ", None), Ok(0));
+        assert!(!pool.members.contains_key("carol"));
+        assert!(pool.withdraw("carol", None).is_err());
+        assert!(pool.cancel().is_err());
+
+        // Cancelling a purchase gives its contributions back to the pool
+        pool.buy(date(), 42, false).unwrap();
+        pool.contribute("dave", 200).unwrap();
+        assert!(pool.withdraw("alice", Some(100)).is_err());
+        assert_eq!(pool.cancel().unwrap().tickets.len(), 7);
+        assert_eq!(pool.draw, None);
+        assert_eq!(pool.budget(), 1600);
+        assert_eq!(pool.withdraw("alice", Some(100)), Ok(900));
+    }
+
+    #[test]
+    fn winnings_are_shared_by_contribution() {
+        let mut pool = office();
+        let tickets = pool.buy(date(), 42, false).unwrap().tickets.clone();
+        let first = &tickets[0];
+
+        // Five of the first ticket's numbers plus its sixth as the bonus: 2等
+        let others: Vec<u32> = (1..=43).filter(|n| !first.contains(n)).collect();
+        let mut main = first[..5].to_vec();
+        main.push(others[0]);
+        main.sort();
+        let winning = Drawing {
+            main,
+            bonus: vec![first[5]],
+        };
+        let settlement = pool
+            .enter_result(winning.clone(), &BTreeMap::from([(2, 9_000_001)]))
+            .unwrap();
+        let second = settlement.wins.iter().find(|w| w.ticket == 1).unwrap();
+        assert_eq!((second.tier, second.prize), (2, 9_000_001));
+        assert_eq!(
+            settlement.winnings,
+            settlement.wins.iter().map(|w| w.prize).sum::<u64>()
+        );
+        let payouts: Vec<u64> = settlement.shares.iter().map(|s| s.payout).collect();
+        assert_eq!(payouts.iter().sum::<u64>(), settlement.winnings);
+        assert!(payouts[0] > payouts[1] && payouts[1] > payouts[2]);
+
+        let csv = settlement.to_csv();
+        assert!(csv.starts_with(
+            "member,contribution,share_percent,payout,target_draw,draw_date\nalice,1000,50.00,"
+        ));
+        assert_eq!(csv.lines().count(), 4);
+        let number = settlement.number.unwrap();
+        assert!(csv
+            .lines()
+            .skip(1)
+            .all(|line| line.ends_with(&format!(",{},2024-02-15", number))));
+        assert_eq!(pool.settlement(), Some(settlement.clone()));
+
+        // Announced amounts replace the prizes of the recorded result, tier by tier
+        let updated = pool.update_prizes(&BTreeMap::from([(5, 1_100)])).unwrap();
+        for win in &updated.wins {
+            let expected = match win.tier {
+                2 => 9_000_001,
+                5 => 1_100,
+                tier => GameType::Loto6.reference_prizes()[tier as usize - 1],
+            };
+            assert_eq!(win.prize, expected);
+        }
+        assert_eq!(pool.settlement(), Some(updated));
+        assert!(pool.update_prizes(&BTreeMap::from([(9, 1)])).is_err());
+        assert!(office().update_prizes(&BTreeMap::new()).is_err());
+
+        // Bad numbers and unknown tiers are refused
+        let mut short = winning.clone();
+        short.main.pop();
+        assert!(pool.enter_result(short, &BTreeMap::new()).is_err());
+        let mut repeated = winning.clone();
+        repeated.bonus = vec![repeated.main[0]];
+        assert!(pool.enter_result(repeated, &BTreeMap::new()).is_err());
+        assert!(pool
+            .enter_result(winning.clone(), &BTreeMap::from([(6, 1)]))
+            .is_err());
+        assert!(pool
+            .enter_result(winning, &BTreeMap::from([(0, 1)]))
+            .is_err());
+    }
+
+    #[test]
+    fn settled_draws_are_kept() {
+        let mut pool = office();
+        pool.buy(date(), 42, false).unwrap();
+        let winning = Drawing {
+            main: vec![1, 2, 3, 4, 5, 6],
+            bonus: vec![7],
+        };
+        let settled = pool.enter_result(winning, &BTreeMap::new()).unwrap();
+
+        // A settled draw can't be bought again, but the pool can move on with new money
+        assert!(pool.buy(date(), 43, true).is_err());
+        let monday = NaiveDate::from_ymd_opt(2024, 2, 19).unwrap();
+        assert!(pool.buy(monday, 43, false).is_err());
+        pool.contribute("dave", 400).unwrap();
+        let draw = pool.buy(monday, 43, false).unwrap();
+        assert_eq!(draw.tickets.len(), 2);
+        assert_eq!(draw.members, BTreeMap::from([("dave".to_string(), 400)]));
+        assert!(draw.number.is_some());
+        assert!(draw.result.is_none());
+
+        assert_eq!(pool.history.len(), 1);
+        assert_eq!(pool.history[0].date, date());
+        assert_eq!(pool.history[0].members.len(), 3);
+        assert_eq!(pool.history[0].settlement(pool.game), Some(settled));
+        assert_eq!(pool.settlement(), None);
+    }
+
+    #[test]
+    fn pools_survive_the_file() {
+        let mut pools = Pools::default();
+        let pool = pools.create("office", GameType::Mini).unwrap();
+        pool.contribute("山田 太郎", 1000).unwrap();
+        let tuesday = NaiveDate::from_ymd_opt(2024, 2, 13).unwrap();
+        pool.buy(tuesday, 7, false).unwrap();
+        let winning = Drawing {
+            main: vec![1, 2, 3, 4, 5],
+            bonus: vec![6],
+        };
+        pool.enter_result(winning, &BTreeMap::new()).unwrap();
+        pool.contribute("山田 太郎", 400).unwrap();
+        pool.buy(tuesday + chrono::Days::new(7), 8, false).unwrap();
+        pool.contribute("佐藤", 200).unwrap();
+        assert!(pools.create("office", GameType::Loto6).is_err());
+        assert!(pools.create("bad key", GameType::Loto6).is_err());
+
+        let text = toml::to_string(&pools).unwrap();
+        let back = Pools::parse(&text).unwrap();
+        assert_eq!(back.pools, pools.pools);
+        assert!(back.get("nobody").is_err());
+        assert!(Pools::parse("[pools.x]\ngame = \"loto6\"\nextra = 1\n").is_err());
+    }
+}
@@ -0,0 +1,293 @@
+//! Named seeker profiles stored in `profiles.toml` under the config directory, so the
+//! Oracle inputs don't have to be retyped on every run.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::oracle::{AuraColor, BloodType, Gender};
+use crate::seimei::NameReading;
+
+/// Favorites must fit the largest game (Loto6, 1–43).
+const MAX_FAVORITE: u32 = 43;
+
+/// The Oracle inputs of one person.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub birth_date: Option<NaiveDate>,
+    pub blood_type: Option<BloodType>,
+    pub aura_color: Option<AuraColor>,
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub favorite_numbers: Vec<u32>,
+    pub gender: Option<Gender>,
+}
+
+impl Profile {
+    /// Fields set in `other` win over ours.
+    pub fn overridden_by(self, other: Profile) -> Profile {
+        Profile {
+            birth_date: other.birth_date.or(self.birth_date),
+            blood_type: other.blood_type.or(self.blood_type),
+            aura_color: other.aura_color.or(self.aura_color),
+            name: other.name.or(self.name),
+            favorite_numbers: if other.favorite_numbers.is_empty() {
+                self.favorite_numbers
+            } else {
+                other.favorite_numbers
+            },
+            gender: other.gender.or(self.gender),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(name) = &self.name {
+            NameReading::parse(name)?;
+        }
+        for (i, &n) in self.favorite_numbers.iter().enumerate() {
+            if !(1..=MAX_FAVORITE).contains(&n) {
+                return Err(format!(
+                    "お気に入りの数字は 1〜{} で指定してください: {}",
+                    MAX_FAVORITE, n
+                ));
+            }
+            if self.favorite_numbers[..i].contains(&n) {
+                return Err(format!("お気に入りの数字が重複しています: {}", n));
+            }
+        }
+        Ok(())
+    }
+
+    /// One-line summary for `profile list`.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(d) = self.birth_date {
+            parts.push(d.to_string());
+        }
+        if let Some(b) = self.blood_type {
+            parts.push(format!("{:?}型", b));
+        }
+        if let Some(a) = self.aura_color {
+            parts.push(format!("{:?}", a));
+        }
+        if let Some(g) = self.gender {
+            parts.push(format!("{:?}", g));
+        }
+        if let Some(n) = &self.name {
+            parts.push(n.clone());
+        }
+        if !self.favorite_numbers.is_empty() {
+            let favorites: Vec<String> = self
+                .favorite_numbers
+                .iter()
+                .map(|n| n.to_string())
+                .collect();
+            parts.push(format!("★{}", favorites.join(",")));
+        }
+        parts.join(" / ")
+    }
+}
+
+/// The whole profile file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profiles {
+    /// Profile loaded when `--profile` is not given (set by `profile use`)
+    pub default: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+fn path() -> Result<PathBuf, String> {
+    crate::config::config_dir()
+        .map(|dir| dir.join("profiles.toml"))
+        .ok_or_else(|| "設定ディレクトリが見つかりません (HOME 未設定)".to_string())
+}
+
+/// Profile names double as TOML keys and CLI values, so keep them plain.
+fn validate_key(key: &str) -> Result<(), String> {
+    let plain = key
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if key.is_empty() || !plain {
+        return Err(format!(
+            "プロファイル名は英数字・-・_ で指定してください: {}",
+            key
+        ));
+    }
+    Ok(())
+}
+
+impl Profiles {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let profiles: Profiles = toml::from_str(text).map_err(|e| e.to_string())?;
+        for (key, profile) in &profiles.profiles {
+            validate_key(key)?;
+            profile.validate().map_err(|e| format!("[{}] {}", key, e))?;
+        }
+        Ok(profiles)
+    }
+
+    /// Reads the profile file; a missing file is an empty set of profiles.
+    pub fn load() -> Result<Self, String> {
+        let path = path()?;
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn save(&self) -> Result<PathBuf, String> {
+        let path = path()?;
+        let text = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::write(&path, text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    pub fn get(&self, key: &str) -> Result<&Profile, String> {
+        self.profiles.get(key).ok_or_else(|| {
+            format!(
+                "プロファイルが見つかりません: {} (`profile list` で一覧を確認できます)",
+                key
+            )
+        })
+    }
+
+    /// Adds or replaces a profile; returns whether it already existed.
+    pub fn insert(&mut self, key: &str, profile: Profile) -> Result<bool, String> {
+        validate_key(key)?;
+        profile.validate()?;
+        Ok(self.profiles.insert(key.to_string(), profile).is_some())
+    }
+
+    pub fn remove(&mut self, key: &str) -> Result<Profile, String> {
+        let profile = self.get(key)?.clone();
+        self.profiles.remove(key);
+        if self.default.as_deref() == Some(key) {
+            self.default = None;
+        }
+        Ok(profile)
+    }
+
+    pub fn set_default(&mut self, key: &str) -> Result<(), String> {
+        self.get(key)?;
+        self.default = Some(key.to_string());
+        Ok(())
+    }
+
+    /// The profile to use for a run: `--profile`, else the default, else none.
+    pub fn select(&self, key: Option<&str>) -> Result<Option<(String, Profile)>, String> {
+        match key.or(self.default.as_deref()) {
+            Some(key) => Ok(Some((key.to_string(), self.get(key)?.clone()))),
+            None => Ok(None),
+        }
+    }
+
+    /// The profile for a run. An explicit `--profile` has to load; otherwise a missing
+    /// config directory means no profiles, and a broken file only earns a warning.
+    pub fn for_run(key: Option<&str>) -> Result<Option<(String, Profile)>, String> {
+        if key.is_some() {
+            return Self::load()?.select(key);
+        }
+        if crate::config::config_dir().is_none() {
+            return Ok(None);
+        }
+        match Self::load().and_then(|profiles| profiles.select(None)) {
+            Ok(selected) => Ok(selected),
+            Err(e) => {
+                eprintln!("⚠️  Ignoring the stored profiles: {}", e);
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alice() -> Profile {
+        Profile {
+            birth_date: NaiveDate::from_ymd_opt(1990, 1, 20),
+            blood_type: Some(BloodType::AB),
+            aura_color: Some(AuraColor::Gold),
+            name: Some("山田 花子".to_string()),
+            favorite_numbers: vec![7, 21],
+            gender: Some(Gender::Female),
+        }
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut profiles = Profiles::default();
+        profiles.insert("alice", alice()).unwrap();
+        profiles.set_default("alice").unwrap();
+        let text = toml::to_string_pretty(&profiles).unwrap();
+        assert!(text.contains("blood_type = \"ab\""));
+        assert!(text.contains("aura_color = \"gold\""));
+        let back = Profiles::parse(&text).unwrap();
+        assert_eq!(back.default.as_deref(), Some("alice"));
+        assert_eq!(back.get("alice").unwrap(), &alice());
+
+        // Blood types used to be written in upper case
+        let old = Profiles::parse("[profiles.alice]\nblood_type = \"AB\"\n").unwrap();
+        assert_eq!(old.get("alice").unwrap().blood_type, Some(BloodType::AB));
+    }
+
+    #[test]
+    fn selection_and_missing_profiles() {
+        let mut profiles = Profiles::default();
+        assert_eq!(profiles.select(None).unwrap(), None);
+        assert!(profiles.select(Some("bob")).unwrap_err().contains("bob"));
+        assert!(profiles.set_default("bob").is_err());
+
+        profiles.insert("alice", alice()).unwrap();
+        profiles.set_default("alice").unwrap();
+        assert_eq!(profiles.select(None).unwrap().unwrap().0, "alice");
+        profiles.remove("alice").unwrap();
+        assert_eq!(profiles.default, None);
+        assert!(profiles.remove("alice").is_err());
+    }
+
+    #[test]
+    fn invalid_profiles_are_rejected() {
+        let mut profiles = Profiles::default();
+        assert!(profiles.insert("a b", alice()).is_err());
+        let bad_numbers = Profile {
+            favorite_numbers: vec![7, 44],
+            ..alice()
+        };
+        assert!(profiles.insert("alice", bad_numbers).is_err());
+        let duplicate = Profile {
+            favorite_numbers: vec![7, 7],
+            ..alice()
+        };
+        assert!(profiles.insert("alice", duplicate).is_err());
+        let bad_name = Profile {
+            name: Some("山田花子".to_string()),
+            ..alice()
+        };
+        assert!(profiles.insert("alice", bad_name).is_err());
+        assert!(Profiles::parse("[profiles.x]\nblood_type = \"C\"\n").is_err());
+    }
+
+    #[test]
+    fn command_line_overrides_the_profile() {
+        let cli = Profile {
+            blood_type: Some(BloodType::O),
+            ..Profile::default()
+        };
+        let merged = alice().overridden_by(cli);
+        assert!(matches!(merged.blood_type, Some(BloodType::O)));
+        assert_eq!(merged.birth_date, alice().birth_date);
+        assert_eq!(merged.favorite_numbers, vec![7, 21]);
+    }
+}
@@ -0,0 +1,192 @@
+//! The extended observer ritual: keypress intervals gathered in raw terminal mode,
+//! scored for min-entropy and condensed into a seed with SHA-256.
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::time::Instant;
+
+/// Bytes arriving closer together than this belong to one keypress (escape sequences).
+const SAME_KEY_NANOS: u64 = 1_000_000;
+
+/// The timings of one ritual.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ritual {
+    /// Nanoseconds between consecutive keypresses.
+    intervals: Vec<u64>,
+}
+
+impl Ritual {
+    /// Waits for `presses` keypresses and records the gaps between them. Reads single
+    /// keys when stdin is a terminal, whole lines (one per ENTER) otherwise.
+    pub fn perform(presses: usize) -> io::Result<Self> {
+        eprintln!(
+            "🌌 The extended ritual: press any key {} times, letting the moment choose each.",
+            presses
+        );
+        eprint!("   ");
+        let raw = RawMode::enable()?;
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+        let mut last = Instant::now();
+        let mut intervals = Vec::with_capacity(presses);
+        let mut byte = [0u8; 1];
+        let mut line = String::new();
+
+        while intervals.len() < presses {
+            let read = if raw.is_some() {
+                input.read(&mut byte)?
+            } else {
+                line.clear();
+                io::BufRead::read_line(&mut input, &mut line)?
+            };
+            if read == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "ritual interrupted: input ended",
+                ));
+            }
+            // Ctrl-C and Ctrl-D, which raw mode no longer turns into signals
+            if raw.is_some() && matches!(byte[0], 3 | 4) {
+                return Err(io::Error::new(
+                    io::ErrorKind::Interrupted,
+                    "ritual interrupted",
+                ));
+            }
+            let now = Instant::now();
+            let gap = now.duration_since(last).as_nanos() as u64;
+            if raw.is_some() && gap < SAME_KEY_NANOS {
+                continue;
+            }
+            last = now;
+            intervals.push(gap);
+            eprint!("✦");
+        }
+        eprintln!();
+        Ok(Ritual { intervals })
+    }
+
+    /// A conservative min-entropy estimate of the whole ritual in bits: the
+    /// most-common-value estimate (NIST SP 800-90B 6.3.1) over the low 8 bits of
+    /// each interval in microseconds, times the number of intervals.
+    pub fn min_entropy_bits(&self) -> f64 {
+        let n = self.intervals.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let mut counts: HashMap<u8, usize> = HashMap::new();
+        for &gap in &self.intervals {
+            *counts.entry((gap / 1_000) as u8).or_default() += 1;
+        }
+        let most = counts.values().copied().max().unwrap_or(n);
+        let p = most as f64 / n as f64;
+        // Upper bound of the 99% confidence interval
+        let p_upper = (p + 2.576 * (p * (1.0 - p) / (n - 1) as f64).sqrt()).min(1.0);
+        -p_upper.log2() * n as f64
+    }
+
+    fn digest(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        hasher.update(b"loto_random_cli/ritual");
+        for gap in &self.intervals {
+            hasher.update(gap.to_be_bytes());
+        }
+        hasher.finalize().into()
+    }
+
+    /// The run's seed: the first 8 bytes of the digest.
+    pub fn seed(&self) -> u64 {
+        u64::from_be_bytes(self.digest()[..8].try_into().unwrap())
+    }
+
+    /// The Oracle's resonance: the last 16 bytes of the digest.
+    pub fn resonance(&self) -> u128 {
+        u128::from_be_bytes(self.digest()[16..].try_into().unwrap())
+    }
+}
+
+/// Puts the terminal into non-canonical, no-echo mode until dropped.
+#[cfg(unix)]
+struct RawMode {
+    original: libc::termios,
+}
+
+#[cfg(unix)]
+impl RawMode {
+    /// `None` when stdin is not a terminal.
+    fn enable() -> io::Result<Option<Self>> {
+        // SAFETY: plain termios calls on stdin with a zeroed struct for tcgetattr to fill
+        unsafe {
+            if libc::isatty(libc::STDIN_FILENO) == 0 {
+                return Ok(None);
+            }
+            let mut original: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let mut raw = original;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+            raw.c_cc[libc::VMIN] = 1;
+            raw.c_cc[libc::VTIME] = 0;
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Some(RawMode { original }))
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // SAFETY: restores the attributes read in enable()
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+/// Elsewhere the ritual falls back to one keypress per line.
+#[cfg(not(unix))]
+struct RawMode;
+
+#[cfg(not(unix))]
+impl RawMode {
+    fn enable() -> io::Result<Option<Self>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ritual(intervals: Vec<u64>) -> Ritual {
+        Ritual { intervals }
+    }
+
+    #[test]
+    fn metronome_timings_carry_no_entropy() {
+        let metronome = ritual(vec![250_000_000; 32]);
+        assert_eq!(metronome.min_entropy_bits(), 0.0);
+        assert_eq!(ritual(vec![1]).min_entropy_bits(), 0.0);
+    }
+
+    #[test]
+    fn jittery_timings_are_credited_conservatively() {
+        // 32 distinct low bytes: p = 1/32, raised to about 0.11 by the confidence bound
+        let intervals = (0..32u64).map(|i| 200_000_000 + i * 7_000).collect();
+        let bits = ritual(intervals).min_entropy_bits();
+        assert!(bits > 90.0 && bits < 110.0, "{}", bits);
+    }
+
+    #[test]
+    fn seed_and_resonance_follow_the_timings() {
+        let a = ritual(vec![301_234_567, 287_654_321, 412_345_678]);
+        let b = ritual(vec![301_234_567, 287_654_321, 412_345_679]);
+        assert_eq!(a.seed(), a.clone().seed());
+        assert_ne!(a.seed(), b.seed());
+        assert_ne!(a.resonance(), b.resonance());
+        assert_ne!(a.seed() as u128, a.resonance() & u64::MAX as u128);
+    }
+}
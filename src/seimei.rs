@@ -0,0 +1,293 @@
+//! 姓名判断: stroke counts (画数) of a Japanese name, or Pythagorean numerology for
+//! names written in Latin letters.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Embedded stroke-count table (新字体).
+const STROKE_TABLE: &str = include_str!("data/strokes.txt");
+
+/// Kana in 五十音 order with their stroke counts. Small and voiced kana are resolved
+/// through [`KANA_VARIANTS`].
+#[rustfmt::skip]
+const HIRAGANA: &[(char, u32)] = &[
+    ('あ', 3), ('い', 2), ('う', 2), ('え', 2), ('お', 3),
+    ('か', 3), ('き', 4), ('く', 1), ('け', 3), ('こ', 2),
+    ('さ', 3), ('し', 1), ('す', 2), ('せ', 3), ('そ', 1),
+    ('た', 4), ('ち', 3), ('つ', 1), ('て', 1), ('と', 2),
+    ('な', 4), ('に', 3), ('ぬ', 2), ('ね', 2), ('の', 1),
+    ('は', 3), ('ひ', 1), ('ふ', 4), ('へ', 1), ('ほ', 4),
+    ('ま', 3), ('み', 2), ('む', 3), ('め', 2), ('も', 3),
+    ('や', 3), ('ゆ', 2), ('よ', 2),
+    ('ら', 3), ('り', 2), ('る', 1), ('れ', 2), ('ろ', 1),
+    ('わ', 2), ('を', 3), ('ん', 1),
+];
+
+#[rustfmt::skip]
+const KATAKANA: &[(char, u32)] = &[
+    ('ア', 2), ('イ', 2), ('ウ', 3), ('エ', 3), ('オ', 3),
+    ('カ', 2), ('キ', 3), ('ク', 2), ('ケ', 3), ('コ', 2),
+    ('サ', 3), ('シ', 3), ('ス', 2), ('セ', 2), ('ソ', 2),
+    ('タ', 3), ('チ', 3), ('ツ', 3), ('テ', 3), ('ト', 2),
+    ('ナ', 2), ('ニ', 2), ('ヌ', 2), ('ネ', 4), ('ノ', 1),
+    ('ハ', 2), ('ヒ', 2), ('フ', 1), ('ヘ', 1), ('ホ', 4),
+    ('マ', 2), ('ミ', 3), ('ム', 2), ('メ', 2), ('モ', 3),
+    ('ヤ', 2), ('ユ', 2), ('ヨ', 3),
+    ('ラ', 2), ('リ', 2), ('ル', 2), ('レ', 1), ('ロ', 3),
+    ('ワ', 2), ('ヲ', 3), ('ン', 2), ('ー', 1),
+];
+
+/// 総格 counts considered fortunate in the 81-number (八十一数) scheme.
+const LUCKY_COUNTS: &[u32] = &[
+    1, 3, 5, 6, 7, 8, 11, 13, 15, 16, 17, 18, 21, 23, 24, 25, 29, 31, 32, 33, 35, 37, 39, 41, 45,
+    47, 48, 52, 57, 61, 63, 65, 67, 68, 81,
+];
+
+fn stroke_table() -> &'static HashMap<char, u32> {
+    static TABLE: OnceLock<HashMap<char, u32>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table: HashMap<char, u32> = HIRAGANA.iter().chain(KATAKANA).copied().collect();
+        for line in STROKE_TABLE.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((count, chars)) = line.split_once(' ') {
+                if let Ok(count) = count.parse() {
+                    table.extend(chars.chars().map(|c| (c, count)));
+                }
+            }
+        }
+        table
+    })
+}
+
+/// Voiced (濁音), half-voiced (半濁音) and small kana, each paired position by position
+/// with its plain form and the strokes its marks add.
+const KANA_VARIANTS: &[(&str, &str, u32)] = &[
+    (
+        "がぎぐげござじずぜぞだぢづでどばびぶべぼゔガギグゲゴザジズゼゾダヂヅデドバビブベボヴ",
+        "かきくけこさしすせそたちつてとはひふへほうカキクケコサシスセソタチツテトハヒフヘホウ",
+        2,
+    ),
+    ("ぱぴぷぺぽパピプペポ", "はひふへほハヒフヘホ", 1),
+    (
+        "ぁぃぅぇぉっゃゅょゎァィゥェォッャュョヮ",
+        "あいうえおつやゆよわアイウエオツヤユヨワ",
+        0,
+    ),
+];
+
+/// The plain kana behind `c` and the strokes of its marks: が -> (か, 2).
+fn base_kana(c: char) -> Option<(char, u32)> {
+    KANA_VARIANTS.iter().find_map(|(variants, bases, marks)| {
+        let i = variants.chars().position(|v| v == c)?;
+        bases.chars().nth(i).map(|base| (base, *marks))
+    })
+}
+
+/// Stroke count of one character, or `None` if the table does not know it.
+fn strokes(c: char) -> Option<u32> {
+    let table = stroke_table();
+    if let Some(&n) = table.get(&c) {
+        return Some(n);
+    }
+    let (base, marks) = base_kana(c)?;
+    table.get(&base).map(|n| n + marks)
+}
+
+/// Strokes of each character of a name part; 々 repeats the previous character.
+fn part_strokes(part: &str) -> Result<Vec<u32>, String> {
+    let mut counts: Vec<u32> = Vec::new();
+    for c in part.chars() {
+        let n = if c == '々' {
+            counts.last().copied()
+        } else {
+            strokes(c)
+        };
+        counts.push(n.ok_or_else(|| format!("画数表にない文字です: {}", c))?);
+    }
+    Ok(counts)
+}
+
+/// Pythagorean value of a letter: A, J, S = 1 … I, R = 9.
+fn letter_value(c: char) -> u32 {
+    (c.to_ascii_uppercase() as u32 - 'A' as u32) % 9 + 1
+}
+
+/// Digit sum down to a single digit, keeping the master numbers 11, 22 and 33.
+fn reduce(mut n: u32) -> u32 {
+    while n > 9 && !matches!(n, 11 | 22 | 33) {
+        n = std::iter::successors(Some(n), |&x| (x >= 10).then_some(x / 10))
+            .map(|x| x % 10)
+            .sum();
+    }
+    n
+}
+
+/// The result of reading a name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameReading {
+    /// 五格 from the stroke counts of 姓 and 名
+    Seimei {
+        ten: u32,
+        jin: u32,
+        chi: u32,
+        sou: u32,
+    },
+    /// Numerology of a Latin-letter name
+    Pythagorean {
+        expression: u32,
+        soul_urge: u32,
+        personality: u32,
+    },
+}
+
+impl NameReading {
+    /// Reads "姓 名" (space separated, full-width spaces allowed) or a Latin name.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("名前が空です".to_string());
+        }
+        if name
+            .chars()
+            .all(|c| c.is_ascii_alphabetic() || c.is_whitespace() || "-'.".contains(c))
+        {
+            if !name.chars().any(|c| c.is_ascii_alphabetic()) {
+                return Err(format!("名前に英字がありません: {}", name));
+            }
+            return Ok(Self::pythagorean(name));
+        }
+
+        let parts: Vec<&str> = name.split_whitespace().collect();
+        let [surname, given] = parts[..] else {
+            return Err(format!(
+                "姓と名をスペースで区切ってください (例: \"山田 太郎\"): {}",
+                name
+            ));
+        };
+        let surname = part_strokes(surname)?;
+        let given = part_strokes(given)?;
+        let sum = |v: &[u32]| v.iter().sum::<u32>();
+        // One-character parts borrow a 霊数 of 1
+        let spirit = |v: &[u32]| if v.len() == 1 { 1 } else { 0 };
+
+        Ok(NameReading::Seimei {
+            ten: sum(&surname) + spirit(&surname),
+            jin: surname.last().unwrap_or(&0) + given.first().unwrap_or(&0),
+            chi: sum(&given) + spirit(&given),
+            sou: sum(&surname) + sum(&given),
+        })
+    }
+
+    fn pythagorean(name: &str) -> Self {
+        let letters: Vec<char> = name.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+        let is_vowel = |c: &&char| "AEIOU".contains(c.to_ascii_uppercase());
+        let total =
+            |it: &mut dyn Iterator<Item = &char>| reduce(it.map(|&c| letter_value(c)).sum());
+        NameReading::Pythagorean {
+            expression: total(&mut letters.iter()),
+            soul_urge: total(&mut letters.iter().filter(is_vowel)),
+            personality: total(&mut letters.iter().filter(|c| !is_vowel(c))),
+        }
+    }
+
+    /// The derived numbers with their names, in display order.
+    pub fn numbers(&self) -> Vec<(&'static str, u32)> {
+        match *self {
+            NameReading::Seimei { ten, jin, chi, sou } => {
+                vec![("天格", ten), ("人格", jin), ("地格", chi), ("総格", sou)]
+            }
+            NameReading::Pythagorean {
+                expression,
+                soul_urge,
+                personality,
+            } => vec![
+                ("Expression", expression),
+                ("Soul Urge", soul_urge),
+                ("Personality", personality),
+            ],
+        }
+    }
+
+    /// Whether the overall count is auspicious: 総格 in the 81-number scheme, or a
+    /// master number for Latin names.
+    pub fn is_auspicious(&self) -> bool {
+        match *self {
+            NameReading::Seimei { sou, .. } => LUCKY_COUNTS.contains(&((sou - 1) % 80 + 1)),
+            NameReading::Pythagorean { expression, .. } => expression > 9,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kanji_names() {
+        let reading = NameReading::parse("山田 太郎").unwrap();
+        // 山3 田5 / 太4 郎9
+        assert_eq!(
+            reading,
+            NameReading::Seimei {
+                ten: 8,
+                jin: 9,
+                chi: 13,
+                sou: 21
+            }
+        );
+        assert!(reading.is_auspicious());
+    }
+
+    #[test]
+    fn one_character_parts_and_repeats() {
+        // 林8+霊数1 / 翼17+霊数1
+        let reading = NameReading::parse("林　翼").unwrap();
+        assert_eq!(reading.numbers()[0], ("天格", 9));
+        assert_eq!(reading.numbers()[2], ("地格", 18));
+        // 佐7 々7 木4
+        let reading = NameReading::parse("佐々木 優").unwrap();
+        assert_eq!(reading.numbers()[0], ("天格", 18));
+    }
+
+    #[test]
+    fn kana_strokes() {
+        assert_eq!(strokes('か'), Some(3));
+        assert_eq!(strokes('が'), Some(5));
+        assert_eq!(strokes('ぱ'), Some(4));
+        assert_eq!(strokes('ば'), Some(5));
+        assert_eq!(strokes('ゃ'), Some(3));
+        assert_eq!(strokes('ガ'), Some(4));
+        assert_eq!(strokes('ポ'), Some(5));
+        assert_eq!(strokes('ヴ'), Some(5));
+    }
+
+    #[test]
+    fn latin_names() {
+        // J1 O6 H8 N5 / vowels O / consonants J H N
+        let reading = NameReading::parse("John").unwrap();
+        assert_eq!(
+            reading,
+            NameReading::Pythagorean {
+                expression: 2,
+                soul_urge: 6,
+                personality: 5
+            }
+        );
+        assert_eq!(reduce(29), 11);
+        assert_eq!(reduce(38), 11);
+        assert_eq!(reduce(47), 11);
+        assert_eq!(reduce(49), 4);
+    }
+
+    #[test]
+    fn invalid_names() {
+        assert!(NameReading::parse("").is_err());
+        assert!(NameReading::parse("山田太郎").is_err());
+        assert!(NameReading::parse("山田 𠮷郎").is_err());
+        assert!(NameReading::parse("---").is_err());
+        assert!(NameReading::parse(" . ' ").is_err());
+    }
+}
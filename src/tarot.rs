@@ -0,0 +1,211 @@
+//! A 78-card tarot deck (Rider–Waite order) and the spreads drawn from it.
+
+use clap::ValueEnum;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Major arcana 0 – 21 with their upright meaning.
+const MAJOR_ARCANA: [(&str, &str); 22] = [
+    ("The Fool", "new beginnings"),
+    ("The Magician", "willpower"),
+    ("The High Priestess", "intuition"),
+    ("The Empress", "abundance"),
+    ("The Emperor", "structure"),
+    ("The Hierophant", "tradition"),
+    ("The Lovers", "union"),
+    ("The Chariot", "victory"),
+    ("Strength", "courage"),
+    ("The Hermit", "solitude"),
+    ("Wheel of Fortune", "turning fate"),
+    ("Justice", "balance"),
+    ("The Hanged Man", "surrender"),
+    ("Death", "transformation"),
+    ("Temperance", "moderation"),
+    ("The Devil", "temptation"),
+    ("The Tower", "upheaval"),
+    ("The Star", "hope"),
+    ("The Moon", "illusion"),
+    ("The Sun", "joy"),
+    ("Judgement", "awakening"),
+    ("The World", "completion"),
+];
+
+const RANKS: [&str; 14] = [
+    "Ace", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Page",
+    "Knight", "Queen", "King",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suit {
+    Wands,
+    Cups,
+    Swords,
+    Pentacles,
+}
+
+impl Suit {
+    const ALL: [Suit; 4] = [Suit::Wands, Suit::Cups, Suit::Swords, Suit::Pentacles];
+
+    pub fn meaning(&self) -> &'static str {
+        match self {
+            Suit::Wands => "passion",
+            Suit::Cups => "emotion",
+            Suit::Swords => "intellect",
+            Suit::Pentacles => "material gain",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arcana {
+    /// 0 (The Fool) – 21 (The World)
+    Major(u8),
+    /// Rank 1 (Ace) – 14 (King)
+    Minor(Suit, u8),
+}
+
+/// A card as it landed on the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Card {
+    pub arcana: Arcana,
+    pub reversed: bool,
+}
+
+impl Card {
+    pub fn name(&self) -> String {
+        match self.arcana {
+            Arcana::Major(n) => MAJOR_ARCANA[n as usize].0.to_string(),
+            Arcana::Minor(suit, rank) => format!("{} of {:?}", RANKS[rank as usize - 1], suit),
+        }
+    }
+
+    pub fn meaning(&self) -> String {
+        let upright = match self.arcana {
+            Arcana::Major(n) => MAJOR_ARCANA[n as usize].1.to_string(),
+            Arcana::Minor(suit, rank) => match rank {
+                1 => format!("the seed of {}", suit.meaning()),
+                11..=14 => format!("a messenger of {}", suit.meaning()),
+                _ => format!("{} in motion", suit.meaning()),
+            },
+        };
+        if self.reversed {
+            format!("blocked {}", upright)
+        } else {
+            upright
+        }
+    }
+}
+
+impl std::fmt::Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())?;
+        if self.reversed {
+            write!(f, " (R)")?;
+        }
+        Ok(())
+    }
+}
+
+/// The full deck in order: major arcana, then each suit Ace to King.
+pub fn deck() -> Vec<Arcana> {
+    (0..22)
+        .map(Arcana::Major)
+        .chain(
+            Suit::ALL
+                .iter()
+                .flat_map(|&suit| (1..=14).map(move |rank| Arcana::Minor(suit, rank))),
+        )
+        .collect()
+}
+
+/// タロットのスプレッド
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Spread {
+    /// 1枚引き
+    One,
+    /// 過去・現在・未来
+    #[default]
+    Three,
+    /// ケルト十字
+    CelticCross,
+}
+
+impl Spread {
+    /// Position names, one per card.
+    pub fn positions(&self) -> &'static [&'static str] {
+        match self {
+            Spread::One => &["Answer"],
+            Spread::Three => &["Past", "Present", "Future"],
+            Spread::CelticCross => &[
+                "Present",
+                "Challenge",
+                "Foundation",
+                "Past",
+                "Crown",
+                "Near future",
+                "Self",
+                "Environment",
+                "Hopes and fears",
+                "Outcome",
+            ],
+        }
+    }
+
+    /// Shuffles a fresh deck and lays out the spread; each card lands reversed
+    /// with probability 1/2.
+    pub fn draw<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<Card> {
+        let mut deck = deck();
+        deck.shuffle(rng);
+        deck.into_iter()
+            .take(self.positions().len())
+            .map(|arcana| Card {
+                arcana,
+                reversed: rng.gen_bool(0.5),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn deck_has_78_distinct_cards() {
+        let deck = deck();
+        assert_eq!(deck.len(), 78);
+        for (i, a) in deck.iter().enumerate() {
+            assert!(!deck[i + 1..].contains(a));
+        }
+    }
+
+    #[test]
+    fn spreads_are_reproducible_and_distinct() {
+        for spread in [Spread::One, Spread::Three, Spread::CelticCross] {
+            let a = spread.draw(&mut ChaCha8Rng::seed_from_u64(7));
+            let b = spread.draw(&mut ChaCha8Rng::seed_from_u64(7));
+            assert_eq!(a, b);
+            assert_eq!(a.len(), spread.positions().len());
+            for (i, card) in a.iter().enumerate() {
+                assert!(a[i + 1..].iter().all(|c| c.arcana != card.arcana));
+            }
+        }
+    }
+
+    #[test]
+    fn card_names() {
+        let card = Card {
+            arcana: Arcana::Minor(Suit::Cups, 12),
+            reversed: true,
+        };
+        assert_eq!(card.to_string(), "Knight of Cups (R)");
+        assert_eq!(card.meaning(), "blocked a messenger of emotion");
+        let fool = Card {
+            arcana: Arcana::Major(0),
+            reversed: false,
+        };
+        assert_eq!(fool.to_string(), "The Fool");
+    }
+}